@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{ItemStruct, LitStr, parse_macro_input};
+use syn::parse::{Parse, ParseStream};
+use syn::{Data, DataStruct, DeriveInput, Expr, Fields, Ident, ItemStruct, LitBool, LitStr, Token, Type, parse_macro_input};
 
 /// Generates a resource error type with constructors for all 16 canonical error categories.
 ///
@@ -8,6 +9,17 @@ use syn::{ItemStruct, LitStr, parse_macro_input};
 /// constructors take only a resource name and bake the GTS type into ResourceInfo.
 /// For all other categories, constructors forward the context and tag with resource_type.
 ///
+/// Default messages can be overridden per constructor with `name = "message"`
+/// pairs after the GTS type; unlisted constructors keep the built-in defaults.
+/// `id = Type` makes the ResourceInfo constructors take a `Type` (converted
+/// with `to_string()`) instead of `impl Into<String>`.
+///
+/// `inject_resource_name = true` makes `resource_exhausted` and
+/// `failed_precondition` take the resource name before the context and
+/// prepend a synthetic violation naming the resource:
+/// - `QuotaViolation { subject: <resource name>, description: <gts type> }`
+/// - `PreconditionViolation { type: "RESOURCE", subject: <resource name>, description: <gts type> }`
+///
 /// # Example
 ///
 /// ```ignore
@@ -16,15 +28,75 @@ use syn::{ItemStruct, LitStr, parse_macro_input};
 ///
 /// let err = TenantResourceError::not_found("tenant-123");
 /// assert_eq!(err.resource_type(), Some("gts.cf.core.tenants.tenant.v1"));
+///
+/// #[resource_error("gts.cf.core.users.user.v1", not_found = "User not found")]
+/// struct UserResourceError;
+///
+/// assert_eq!(UserResourceError::not_found("user-123").message(), "User not found");
 /// ```
 #[proc_macro_attribute]
 pub fn resource_error(attr: TokenStream, item: TokenStream) -> TokenStream {
-    let gts_type = parse_macro_input!(attr as LitStr);
+    let args = parse_macro_input!(attr as ResourceErrorArgs);
+    let gts_type = &args.gts_type;
     let input = parse_macro_input!(item as ItemStruct);
     let vis = &input.vis;
     let name = &input.ident;
     let attrs = &input.attrs;
 
+    let (name_ty, name_value) = args.resource_name_param();
+    let msg_not_found = args.message_override("not_found");
+    let msg_already_exists = args.message_override("already_exists");
+    let msg_data_loss = args.message_override("data_loss");
+    let msg_invalid_argument = args.message_override("invalid_argument");
+    let msg_permission_denied = args.message_override("permission_denied");
+    let msg_unauthenticated = args.message_override("unauthenticated");
+    let msg_resource_exhausted = args.message_override("resource_exhausted");
+    let msg_failed_precondition = args.message_override("failed_precondition");
+    let msg_aborted = args.message_override("aborted");
+    let msg_out_of_range = args.message_override("out_of_range");
+    let msg_unimplemented = args.message_override("unimplemented");
+    let msg_internal = args.message_override("internal");
+    let msg_unknown = args.message_override("unknown");
+    let msg_deadline_exceeded = args.message_override("deadline_exceeded");
+    let msg_cancelled = args.message_override("cancelled");
+
+    let scoped_constructors = if args.inject_resource_name {
+        quote! {
+            #vis fn resource_exhausted(
+                resource_name: #name_ty,
+                mut ctx: ::canonical_errors::QuotaFailure,
+            ) -> ::canonical_errors::CanonicalError {
+                ctx.violations.insert(0, ::canonical_errors::QuotaViolation::new(#name_value, #gts_type));
+                ::canonical_errors::CanonicalError::resource_exhausted(ctx)
+                    .with_resource_type(#gts_type)#msg_resource_exhausted
+            }
+
+            #vis fn failed_precondition(
+                resource_name: #name_ty,
+                mut ctx: ::canonical_errors::PreconditionFailure,
+            ) -> ::canonical_errors::CanonicalError {
+                ctx.violations.insert(
+                    0,
+                    ::canonical_errors::PreconditionViolation::new("RESOURCE", #name_value, #gts_type),
+                );
+                ::canonical_errors::CanonicalError::failed_precondition(ctx)
+                    .with_resource_type(#gts_type)#msg_failed_precondition
+            }
+        }
+    } else {
+        quote! {
+            #vis fn resource_exhausted(ctx: ::canonical_errors::QuotaFailure) -> ::canonical_errors::CanonicalError {
+                ::canonical_errors::CanonicalError::resource_exhausted(ctx)
+                    .with_resource_type(#gts_type)#msg_resource_exhausted
+            }
+
+            #vis fn failed_precondition(ctx: ::canonical_errors::PreconditionFailure) -> ::canonical_errors::CanonicalError {
+                ::canonical_errors::CanonicalError::failed_precondition(ctx)
+                    .with_resource_type(#gts_type)#msg_failed_precondition
+            }
+        }
+    };
+
     let expanded = quote! {
         #(#attrs)*
         #vis struct #name;
@@ -32,89 +104,303 @@ pub fn resource_error(attr: TokenStream, item: TokenStream) -> TokenStream {
         impl #name {
             // --- ResourceInfo categories: take only resource_name ---
 
-            #vis fn not_found(resource_name: impl Into<String>) -> ::canonical_errors::CanonicalError {
+            #vis fn not_found(resource_name: #name_ty) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::not_found(
-                    ::canonical_errors::ResourceInfo::new(#gts_type, resource_name),
-                ).with_resource_type(#gts_type)
+                    ::canonical_errors::ResourceInfo::new(#gts_type, #name_value),
+                ).with_resource_type(#gts_type)#msg_not_found
             }
 
-            #vis fn already_exists(resource_name: impl Into<String>) -> ::canonical_errors::CanonicalError {
+            #vis fn already_exists(resource_name: #name_ty) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::already_exists(
-                    ::canonical_errors::ResourceInfo::new(#gts_type, resource_name)
+                    ::canonical_errors::ResourceInfo::new(#gts_type, #name_value)
                         .with_description("Resource already exists"),
-                ).with_resource_type(#gts_type)
+                ).with_resource_type(#gts_type)#msg_already_exists
             }
 
-            #vis fn data_loss(resource_name: impl Into<String>) -> ::canonical_errors::CanonicalError {
+            #vis fn data_loss(resource_name: #name_ty) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::data_loss(
-                    ::canonical_errors::ResourceInfo::new(#gts_type, resource_name)
+                    ::canonical_errors::ResourceInfo::new(#gts_type, #name_value)
                         .with_description("Data loss detected"),
-                ).with_resource_type(#gts_type)
+                ).with_resource_type(#gts_type)#msg_data_loss
             }
 
             // --- All other categories: forward context, tag with resource_type ---
 
             #vis fn invalid_argument(ctx: ::canonical_errors::Validation) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::invalid_argument(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_invalid_argument
             }
 
             #vis fn permission_denied(ctx: ::canonical_errors::ErrorInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::permission_denied(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_permission_denied
             }
 
             #vis fn unauthenticated(ctx: ::canonical_errors::ErrorInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::unauthenticated(ctx)
-                    .with_resource_type(#gts_type)
-            }
-
-            #vis fn resource_exhausted(ctx: ::canonical_errors::QuotaFailure) -> ::canonical_errors::CanonicalError {
-                ::canonical_errors::CanonicalError::resource_exhausted(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_unauthenticated
             }
 
-            #vis fn failed_precondition(ctx: ::canonical_errors::PreconditionFailure) -> ::canonical_errors::CanonicalError {
-                ::canonical_errors::CanonicalError::failed_precondition(ctx)
-                    .with_resource_type(#gts_type)
-            }
+            #scoped_constructors
 
             #vis fn aborted(ctx: ::canonical_errors::ErrorInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::aborted(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_aborted
             }
 
             #vis fn out_of_range(ctx: ::canonical_errors::Validation) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::out_of_range(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_out_of_range
             }
 
             #vis fn unimplemented(ctx: ::canonical_errors::ErrorInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::unimplemented(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_unimplemented
             }
 
             #vis fn internal(ctx: ::canonical_errors::DebugInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::internal(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_internal
             }
 
             #vis fn unknown(detail: impl Into<String>) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::unknown(detail)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_unknown
             }
 
             #vis fn deadline_exceeded(ctx: ::canonical_errors::RequestInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::deadline_exceeded(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_deadline_exceeded
             }
 
             #vis fn cancelled(ctx: ::canonical_errors::RequestInfo) -> ::canonical_errors::CanonicalError {
                 ::canonical_errors::CanonicalError::cancelled(ctx)
-                    .with_resource_type(#gts_type)
+                    .with_resource_type(#gts_type)#msg_cancelled
             }
         }
     };
 
     expanded.into()
 }
+
+/// Generated constructor names, which double as message-override keys.
+const CONSTRUCTORS: &[&str] = &[
+    "not_found",
+    "already_exists",
+    "data_loss",
+    "invalid_argument",
+    "permission_denied",
+    "unauthenticated",
+    "resource_exhausted",
+    "failed_precondition",
+    "aborted",
+    "out_of_range",
+    "unimplemented",
+    "internal",
+    "unknown",
+    "deadline_exceeded",
+    "cancelled",
+];
+
+/// `"gts.type.v1"` followed by optional `constructor = "message"` overrides
+/// and the optional `id = Type` and `inject_resource_name = bool` settings.
+struct ResourceErrorArgs {
+    gts_type: LitStr,
+    messages: Vec<(Ident, LitStr)>,
+    id: Option<Type>,
+    inject_resource_name: bool,
+}
+
+impl ResourceErrorArgs {
+    /// Parameter type and `String` conversion for the ResourceInfo constructors.
+    fn resource_name_param(&self) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+        match &self.id {
+            Some(ty) => (
+                quote! { #ty },
+                quote! { ::std::string::ToString::to_string(&resource_name) },
+            ),
+            None => (quote! { impl Into<String> }, quote! { resource_name }),
+        }
+    }
+
+    /// `.with_message(..)` for a constructor with an override, nothing otherwise.
+    fn message_override(&self, constructor: &str) -> proc_macro2::TokenStream {
+        match self.messages.iter().find(|(key, _)| key == constructor) {
+            Some((_, message)) => quote! { .with_message(#message) },
+            None => proc_macro2::TokenStream::new(),
+        }
+    }
+}
+
+impl Parse for ResourceErrorArgs {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let gts_type: LitStr = input.parse()?;
+        let mut messages: Vec<(Ident, LitStr)> = Vec::new();
+        let mut id = None;
+        let mut inject_resource_name = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let key_name = key.to_string();
+            if key_name == "id" {
+                if id.is_some() {
+                    return Err(syn::Error::new(key.span(), "duplicate `id` option"));
+                }
+                id = Some(input.parse()?);
+                continue;
+            }
+            if key_name == "inject_resource_name" {
+                if inject_resource_name.is_some() {
+                    return Err(syn::Error::new(key.span(), "duplicate `inject_resource_name` option"));
+                }
+                inject_resource_name = Some(input.parse::<LitBool>()?.value);
+                continue;
+            }
+            if !CONSTRUCTORS.contains(&key_name.as_str()) {
+                return Err(syn::Error::new(key.span(), format!("unknown resource_error option `{key_name}`")));
+            }
+            if messages.iter().any(|(seen, _)| *seen == key_name) {
+                return Err(syn::Error::new(key.span(), format!("duplicate message override for `{key_name}`")));
+            }
+            messages.push((key, input.parse()?));
+        }
+        Ok(Self {
+            gts_type,
+            messages,
+            id,
+            inject_resource_name: inject_resource_name.unwrap_or(false),
+        })
+    }
+}
+
+/// Derives `fn validate(&self) -> Result<(), CanonicalError>` from `#[validate(...)]`
+/// field attributes. Every failed check adds one `FieldViolation`; any violation
+/// yields an `InvalidArgument`.
+///
+/// Supported checks:
+/// - `required` — `None`, or an empty `String`/`Vec` (reason `REQUIRED`)
+/// - `email` — a string with a non-empty local part and a dotted domain (reason `INVALID_FORMAT`)
+/// - `range(min = .., max = ..)` — numeric bounds, either optional (reason `OUT_OF_RANGE`)
+///
+/// `email` skips `None` and empty strings and `range` skips `None`, so combine
+/// them with `required` when the field must be present; a missing value then
+/// reports only `REQUIRED`.
+///
+/// # Example
+///
+/// ```ignore
+/// #[derive(Validate)]
+/// struct Signup {
+///     #[validate(required, email)]
+///     email: String,
+///     #[validate(range(min = 18))]
+///     age: u8,
+/// }
+///
+/// Signup { email: "nope".into(), age: 12 }.validate()?;
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    match expand_validate(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_validate(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &input.data
+    else {
+        return Err(syn::Error::new_spanned(input, "Validate can only be derived for structs with named fields"));
+    };
+
+    let mut checks = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("required") {
+                    checks.push(quote! {
+                        if ::canonical_errors::__validate::Required::is_missing(&self.#ident) {
+                            builder.push_field(#name, "is required", ::canonical_errors::FieldViolationReason::Required);
+                        }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("email") {
+                    checks.push(quote! {
+                        if let Some(value) = ::canonical_errors::__validate::AsOptStr::as_opt_str(&self.#ident) {
+                            if !value.is_empty() && !::canonical_errors::__validate::is_email(value) {
+                                builder.push_field(
+                                    #name,
+                                    "must be a valid email address",
+                                    ::canonical_errors::FieldViolationReason::InvalidFormat,
+                                );
+                            }
+                        }
+                    });
+                    Ok(())
+                } else if meta.path.is_ident("range") {
+                    let mut min: Option<Expr> = None;
+                    let mut max: Option<Expr> = None;
+                    meta.parse_nested_meta(|bound| {
+                        if bound.path.is_ident("min") {
+                            min = Some(bound.value()?.parse()?);
+                            Ok(())
+                        } else if bound.path.is_ident("max") {
+                            max = Some(bound.value()?.parse()?);
+                            Ok(())
+                        } else {
+                            Err(bound.error("expected `min` or `max`"))
+                        }
+                    })?;
+                    if min.is_none() && max.is_none() {
+                        return Err(meta.error("range needs `min`, `max`, or both"));
+                    }
+                    let text = |expr: &Expr| quote!(#expr).to_string().replace(' ', "");
+                    let description = match (&min, &max) {
+                        (Some(lo), Some(hi)) => format!("must be between {} and {}", text(lo), text(hi)),
+                        (Some(lo), None) => format!("must be at least {}", text(lo)),
+                        (None, Some(hi)) => format!("must be at most {}", text(hi)),
+                        (None, None) => unreachable!(),
+                    };
+                    let below = min.map(|lo| quote!(value < (#lo) as f64)).unwrap_or_else(|| quote!(false));
+                    let above = max.map(|hi| quote!(value > (#hi) as f64)).unwrap_or_else(|| quote!(false));
+                    checks.push(quote! {
+                        if let Some(value) = ::canonical_errors::__validate::AsOptF64::as_opt_f64(&self.#ident) {
+                            if #below || #above {
+                                builder.push_field(#name, #description, ::canonical_errors::FieldViolationReason::OutOfRange);
+                            }
+                        }
+                    });
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported validate check; expected `required`, `email` or `range`"))
+                }
+            })?;
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn validate(&self) -> ::core::result::Result<(), ::canonical_errors::CanonicalError> {
+                #[allow(unused_mut)]
+                let mut builder = ::canonical_errors::ValidationBuilder::new();
+                #(#checks)*
+                match builder.build() {
+                    Some(validation) => Err(::canonical_errors::CanonicalError::invalid_argument(validation)),
+                    None => Ok(()),
+                }
+            }
+        }
+    })
+}
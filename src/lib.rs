@@ -1,9 +1,14 @@
 extern crate self as canonical_errors;
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
+#[cfg(feature = "hash")]
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead};
+use std::sync::Arc;
 
-pub use canonical_errors_macros::resource_error;
+pub use canonical_errors_macros::{Validate, resource_error};
 use gts::schema::GtsSchema;
 use gts_macros::struct_to_gts_schema;
 use serde::de::DeserializeOwned;
@@ -56,9 +61,94 @@ impl FieldViolationV1 {
             reason: reason.into(),
         }
     }
+
+    pub fn with_reason(mut self, reason: FieldViolationReason) -> Self {
+        self.reason = reason.into();
+        self
+    }
+
+    /// Parses `reason` into its typed form; unrecognized values become `Other`.
+    pub fn reason_kind(&self) -> FieldViolationReason {
+        FieldViolationReason::from(self.reason.as_str())
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Well-known `FieldViolation.reason` values, serialized as the
+/// screaming-snake string (`REQUIRED`, `INVALID_FORMAT`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldViolationReason {
+    Required,
+    InvalidFormat,
+    OutOfRange,
+    TooShort,
+    TooLong,
+    ConstraintViolation,
+    Other(String),
+}
+
+impl FieldViolationReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Required => "REQUIRED",
+            Self::InvalidFormat => "INVALID_FORMAT",
+            Self::OutOfRange => "OUT_OF_RANGE",
+            Self::TooShort => "TOO_SHORT",
+            Self::TooLong => "TOO_LONG",
+            Self::ConstraintViolation => "CONSTRAINT_VIOLATION",
+            Self::Other(reason) => reason,
+        }
+    }
+}
+
+impl fmt::Display for FieldViolationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for FieldViolationReason {
+    fn from(reason: &str) -> Self {
+        match reason {
+            "REQUIRED" => Self::Required,
+            "INVALID_FORMAT" => Self::InvalidFormat,
+            "OUT_OF_RANGE" => Self::OutOfRange,
+            "TOO_SHORT" => Self::TooShort,
+            "TOO_LONG" => Self::TooLong,
+            "CONSTRAINT_VIOLATION" => Self::ConstraintViolation,
+            other => Self::Other(other.to_string()),
+        }
+    }
+}
+
+impl From<String> for FieldViolationReason {
+    fn from(reason: String) -> Self {
+        Self::from(reason.as_str())
+    }
+}
+
+impl From<FieldViolationReason> for String {
+    fn from(reason: FieldViolationReason) -> Self {
+        match reason {
+            FieldViolationReason::Other(reason) => reason,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl Serialize for FieldViolationReason {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldViolationReason {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "hash", derive(Hash))]
 #[serde(untagged)]
 pub enum Validation {
     FieldViolations {
@@ -125,6 +215,160 @@ impl Validation {
             constraint: msg.into(),
         }
     }
+
+    pub fn builder() -> ValidationBuilder {
+        ValidationBuilder::default()
+    }
+
+    /// Number of field violations; a format or constraint error counts as one.
+    pub fn violation_count(&self) -> usize {
+        match self {
+            Self::FieldViolations { field_violations } => field_violations.len(),
+            Self::Format { .. } | Self::Constraint { .. } => 1,
+        }
+    }
+}
+
+/// Collects violations into [`Validation::fields`].
+impl FromIterator<FieldViolation> for Validation {
+    fn from_iter<I: IntoIterator<Item = FieldViolation>>(iter: I) -> Self {
+        Self::fields(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// Accumulates field violations discovered across independent checks.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationBuilder {
+    violations: Vec<FieldViolation>,
+}
+
+impl ValidationBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_field(
+        &mut self,
+        field: impl Into<String>,
+        description: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> &mut Self {
+        self.violations.push(FieldViolation::new(field, description, reason));
+        self
+    }
+
+    /// Records a malformed field with reason `INVALID_FORMAT`.
+    pub fn push_format(&mut self, field: impl Into<String>, description: impl Into<String>) -> &mut Self {
+        self.push_field(field, description, FieldViolationReason::InvalidFormat)
+    }
+
+    /// Records a field that breaks a constraint with reason `CONSTRAINT_VIOLATION`.
+    pub fn push_constraint(&mut self, field: impl Into<String>, description: impl Into<String>) -> &mut Self {
+        self.push_field(field, description, FieldViolationReason::ConstraintViolation)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns `None` when nothing was pushed, so callers can short-circuit
+    /// to success.
+    pub fn build(self) -> Option<Validation> {
+        if self.violations.is_empty() {
+            None
+        } else {
+            Some(Validation::fields(self.violations))
+        }
+    }
+}
+
+/// Support code for `#[derive(Validate)]`; not a stable API.
+#[doc(hidden)]
+pub mod __validate {
+    pub trait Required {
+        fn is_missing(&self) -> bool;
+    }
+
+    impl<T> Required for Option<T> {
+        fn is_missing(&self) -> bool {
+            self.is_none()
+        }
+    }
+
+    impl Required for String {
+        fn is_missing(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl Required for &str {
+        fn is_missing(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    impl<T> Required for Vec<T> {
+        fn is_missing(&self) -> bool {
+            self.is_empty()
+        }
+    }
+
+    pub trait AsOptStr {
+        fn as_opt_str(&self) -> Option<&str>;
+    }
+
+    impl AsOptStr for String {
+        fn as_opt_str(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl AsOptStr for &str {
+        fn as_opt_str(&self) -> Option<&str> {
+            Some(self)
+        }
+    }
+
+    impl AsOptStr for Option<String> {
+        fn as_opt_str(&self) -> Option<&str> {
+            self.as_deref()
+        }
+    }
+
+    pub trait AsOptF64 {
+        fn as_opt_f64(&self) -> Option<f64>;
+    }
+
+    macro_rules! as_opt_f64 {
+        ($($ty:ty),*) => {$(
+            impl AsOptF64 for $ty {
+                fn as_opt_f64(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+
+            impl AsOptF64 for Option<$ty> {
+                fn as_opt_f64(&self) -> Option<f64> {
+                    self.map(|value| value as f64)
+                }
+            }
+        )*};
+    }
+
+    as_opt_f64!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+    /// A non-empty local part, one `@`, and a domain with an inner dot.
+    pub fn is_email(value: &str) -> bool {
+        match value.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.split('.').count() > 1
+                    && domain.split('.').all(|label| !label.is_empty())
+            }
+            None => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -133,7 +377,7 @@ impl Validation {
     base = true,
     schema_id = "gts.cf.core.errors.resource_info.v1~",
     description = "Resource identification context for resource-scoped errors",
-    properties = "resource_type,resource_name,description"
+    properties = "resource_type,resource_name,description,parent"
 )]
 pub struct ResourceInfoV1 {
     #[allow(dead_code)]
@@ -142,6 +386,8 @@ pub struct ResourceInfoV1 {
     pub resource_type: String,
     pub resource_name: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Box<ResourceInfoV1>>,
 }
 
 pub type ResourceInfo = ResourceInfoV1;
@@ -152,16 +398,85 @@ impl ResourceInfoV1 {
             gts_type: Self::gts_schema_id().clone(),
             resource_type: resource_type.into(),
             resource_name: resource_name.into(),
-            description: String::from("Resource not found"),
+            description: String::from(Category::NotFound.default_message()),
+            parent: None,
         }
     }
 
+    /// Builds a `ResourceInfo` for a resource identified by several parts,
+    /// joined with `/` (e.g. `["tenant-1", "user-9"]` → `"tenant-1/user-9"`).
+    pub fn composite(resource_type: impl Into<String>, parts: &[&str]) -> Self {
+        Self::new(resource_type, parts.join("/"))
+    }
+
     pub fn with_description(mut self, description: impl Into<String>) -> Self {
         self.description = description.into();
         self
     }
+
+    /// Sets the enclosing resource, e.g. the project a missing user belongs
+    /// to. Parents nest recursively (tenant → project → user); keep chains
+    /// to a handful of levels (at most 16), well under serde_json's default
+    /// recursion limit of 128 when the context is parsed back.
+    ///
+    /// The parent exists, so a description still at the `new` default
+    /// ("Resource not found") is cleared; one set with
+    /// [`with_description`](Self::with_description) is kept.
+    pub fn with_parent(mut self, mut parent: ResourceInfoV1) -> Self {
+        if parent.description == Category::NotFound.default_message() {
+            parent.description.clear();
+        }
+        self.parent = Some(Box::new(parent));
+        self
+    }
+
+    /// Parses a resource URI of the form `gts://<type>~/<name>`, e.g.
+    /// `gts://gts.cf.core.users.user.v1~/user-123`. The type is stored
+    /// without its trailing `~`; the name is everything after the first
+    /// `~/` and may itself contain `/`.
+    pub fn from_uri(uri: &str) -> Result<Self, ResourceUriError> {
+        let rest = uri
+            .strip_prefix("gts://")
+            .ok_or_else(|| ResourceUriError::MissingScheme(uri.to_string()))?;
+        let (resource_type, resource_name) = rest
+            .split_once("~/")
+            .ok_or_else(|| ResourceUriError::MissingSeparator(uri.to_string()))?;
+        if resource_type.is_empty() || resource_name.is_empty() {
+            return Err(ResourceUriError::EmptyComponent(uri.to_string()));
+        }
+        Ok(Self::new(resource_type, resource_name))
+    }
+
+    /// Formats the resource as `gts://<type>~/<name>`, the inverse of
+    /// [`from_uri`](Self::from_uri).
+    pub fn to_uri(&self) -> String {
+        format!("gts://{}~/{}", self.resource_type.trim_end_matches('~'), self.resource_name)
+    }
+}
+
+/// Error returned by [`ResourceInfo::from_uri`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUriError {
+    /// The URI does not start with `gts://`.
+    MissingScheme(String),
+    /// The URI has no `~/` between the type and the name.
+    MissingSeparator(String),
+    /// The type or the name is empty.
+    EmptyComponent(String),
+}
+
+impl fmt::Display for ResourceUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingScheme(uri) => write!(f, "resource URI must start with gts://: {uri}"),
+            Self::MissingSeparator(uri) => write!(f, "resource URI has no ~/ separator: {uri}"),
+            Self::EmptyComponent(uri) => write!(f, "resource URI has an empty type or name: {uri}"),
+        }
+    }
 }
 
+impl std::error::Error for ResourceUriError {}
+
 #[derive(Debug, Clone)]
 #[struct_to_gts_schema(
     dir_path = "schemas",
@@ -176,7 +491,7 @@ pub struct ErrorInfoV1 {
     gts_type: gts::GtsSchemaId,
     pub reason: String,
     pub domain: String,
-    pub metadata: HashMap<String, String>,
+    pub metadata: BTreeMap<String, String>,
 }
 
 pub type ErrorInfo = ErrorInfoV1;
@@ -187,7 +502,7 @@ impl ErrorInfoV1 {
             gts_type: Self::gts_schema_id().clone(),
             reason: reason.into(),
             domain: domain.into(),
-            metadata: HashMap::new(),
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -195,8 +510,24 @@ impl ErrorInfoV1 {
         self.metadata.insert(key.into(), value.into());
         self
     }
+
+    /// Caps `metadata` at `n` entries, keeping the first `n` keys in sorted
+    /// order. When entries are dropped, a `metadata_truncated: "true"` marker
+    /// is added on top of the retained entries.
+    pub fn with_max_metadata(mut self, n: usize) -> Self {
+        if self.metadata.len() <= n {
+            return self;
+        }
+        self.metadata = std::mem::take(&mut self.metadata).into_iter().take(n).collect();
+        self.metadata
+            .insert(String::from(METADATA_TRUNCATED_KEY), String::from("true"));
+        self
+    }
 }
 
+/// Marker key added by [`ErrorInfoV1::with_max_metadata`] when entries were dropped.
+pub const METADATA_TRUNCATED_KEY: &str = "metadata_truncated";
+
 #[derive(Debug, Clone)]
 #[struct_to_gts_schema(
     dir_path = "schemas",
@@ -249,6 +580,53 @@ impl QuotaFailureV1 {
             violations: violations.into(),
         }
     }
+
+    pub fn builder() -> QuotaFailureBuilder {
+        QuotaFailureBuilder::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, QuotaViolation> {
+        self.violations.iter()
+    }
+}
+
+impl FromIterator<QuotaViolation> for QuotaFailureV1 {
+    fn from_iter<I: IntoIterator<Item = QuotaViolation>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// Accumulates quota violations, e.g. one per exceeded rate limit.
+#[derive(Debug, Clone, Default)]
+pub struct QuotaFailureBuilder {
+    violations: Vec<QuotaViolation>,
+}
+
+impl QuotaFailureBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, subject: impl Into<String>, description: impl Into<String>) -> &mut Self {
+        self.violations.push(QuotaViolation::new(subject, description));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn build(self) -> QuotaFailure {
+        QuotaFailure::new(self.violations)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -257,7 +635,7 @@ impl QuotaFailureV1 {
     base = true,
     schema_id = "gts.cf.core.errors.precondition_violation.v1~",
     description = "A single precondition violation entry",
-    properties = "precondition_type,subject,description"
+    properties = "precondition_type,subject,description,subject_path"
 )]
 pub struct PreconditionViolationV1 {
     #[allow(dead_code)]
@@ -267,6 +645,8 @@ pub struct PreconditionViolationV1 {
     pub precondition_type: String,
     pub subject: String,
     pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_path: Option<Vec<String>>,
 }
 
 pub type PreconditionViolation = PreconditionViolationV1;
@@ -282,8 +662,16 @@ impl PreconditionViolationV1 {
             precondition_type: precondition_type.into(),
             subject: subject.into(),
             description: description.into(),
+            subject_path: None,
         }
     }
+
+    /// Locates the subject as a path of segments, e.g. `["tenant", "users"]`,
+    /// alongside the free-form `subject`.
+    pub fn with_subject_path<S: Into<String>>(mut self, path: impl IntoIterator<Item = S>) -> Self {
+        self.subject_path = Some(path.into_iter().map(Into::into).collect());
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -310,6 +698,58 @@ impl PreconditionFailureV1 {
             violations: violations.into(),
         }
     }
+
+    pub fn builder() -> PreconditionFailureBuilder {
+        PreconditionFailureBuilder::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.violations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, PreconditionViolation> {
+        self.violations.iter()
+    }
+}
+
+impl FromIterator<PreconditionViolation> for PreconditionFailureV1 {
+    fn from_iter<I: IntoIterator<Item = PreconditionViolation>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+/// Accumulates precondition violations discovered across independent checks.
+#[derive(Debug, Clone, Default)]
+pub struct PreconditionFailureBuilder {
+    violations: Vec<PreconditionViolation>,
+}
+
+impl PreconditionFailureBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(
+        &mut self,
+        precondition_type: impl Into<String>,
+        subject: impl Into<String>,
+        description: impl Into<String>,
+    ) -> &mut Self {
+        self.violations.push(PreconditionViolation::new(precondition_type, subject, description));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn build(self) -> PreconditionFailure {
+        PreconditionFailure::new(self.violations)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -343,6 +783,57 @@ impl DebugInfoV1 {
         self.stack_entries = entries.into();
         self
     }
+
+    pub fn push(&mut self, entry: impl Into<String>) {
+        self.stack_entries.push(entry.into());
+    }
+
+    /// Captures an error's `source()` chain: the top-level `Display` becomes
+    /// `detail` and each cause is appended as a `"caused by: ..."` stack entry.
+    pub fn from_error_chain(err: &(dyn std::error::Error + 'static)) -> Self {
+        let mut entries = Vec::new();
+        let mut source = err.source();
+        while let Some(cause) = source {
+            entries.push(format!("caused by: {cause}"));
+            source = cause.source();
+        }
+        Self::new(err.to_string()).with_stack(entries)
+    }
+
+    /// Describes a panic payload from `catch_unwind`: `&str` and `String`
+    /// messages become the detail, anything else a generic placeholder.
+    pub fn from_panic(payload: &(dyn std::any::Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&'static str>() {
+            message
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.as_str()
+        } else {
+            "non-string panic payload"
+        };
+        Self::new(format!("panic: {message}"))
+    }
+
+    /// Fills `stack_entries` with the current backtrace, one line per entry.
+    /// Follows `RUST_BACKTRACE`/`RUST_LIB_BACKTRACE`: when capture is
+    /// disabled the existing entries are left untouched.
+    #[cfg(feature = "backtrace")]
+    pub fn with_captured_backtrace(self) -> Self {
+        self.with_backtrace(&std::backtrace::Backtrace::capture())
+    }
+
+    #[cfg(feature = "backtrace")]
+    fn with_backtrace(mut self, backtrace: &std::backtrace::Backtrace) -> Self {
+        if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+            self.stack_entries = backtrace
+                .to_string()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect();
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -351,13 +842,16 @@ impl DebugInfoV1 {
     base = true,
     schema_id = "gts.cf.core.errors.retry_info.v1~",
     description = "Retry information for unavailable errors",
-    properties = "retry_after_seconds"
+    properties = "retry_after_seconds,retry_at"
 )]
 pub struct RetryInfoV1 {
     #[allow(dead_code)]
     #[serde(skip_serializing, default = "dummy_gts_schema_id")]
     gts_type: gts::GtsSchemaId,
-    pub retry_after_seconds: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<String>,
 }
 
 pub type RetryInfo = RetryInfoV1;
@@ -366,7 +860,26 @@ impl RetryInfoV1 {
     pub fn after_seconds(seconds: u64) -> Self {
         Self {
             gts_type: Self::gts_schema_id().clone(),
-            retry_after_seconds: seconds,
+            retry_after_seconds: Some(seconds),
+            retry_at: None,
+        }
+    }
+
+    /// Retry at an absolute point in time, given as an RFC 3339 timestamp.
+    pub fn at(timestamp: impl Into<String>) -> Self {
+        Self {
+            gts_type: Self::gts_schema_id().clone(),
+            retry_after_seconds: None,
+            retry_at: Some(timestamp.into()),
+        }
+    }
+
+    /// No retry hint; the client chooses its own backoff.
+    pub fn unspecified() -> Self {
+        Self {
+            gts_type: Self::gts_schema_id().clone(),
+            retry_after_seconds: None,
+            retry_at: None,
         }
     }
 }
@@ -377,13 +890,15 @@ impl RetryInfoV1 {
     base = true,
     schema_id = "gts.cf.core.errors.request_info.v1~",
     description = "Request identification context",
-    properties = "request_id"
+    properties = "request_id,elapsed_ms"
 )]
 pub struct RequestInfoV1 {
     #[allow(dead_code)]
     #[serde(skip_serializing, default = "dummy_gts_schema_id")]
     gts_type: gts::GtsSchemaId,
     pub request_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
 }
 
 pub type RequestInfo = RequestInfoV1;
@@ -393,111 +908,678 @@ impl RequestInfoV1 {
         Self {
             gts_type: Self::gts_schema_id().clone(),
             request_id: request_id.into(),
+            elapsed_ms: None,
+        }
+    }
+}
+
+/// Why an operation was cancelled, see [`CanonicalError::cancelled_with_reason`].
+/// [`as_str`](Self::as_str) gives the snake-case name (`client_disconnect`, ...).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CancellationReason {
+    ClientDisconnect,
+    ServerShutdown,
+    Timeout,
+    Other(String),
+}
+
+impl CancellationReason {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::ClientDisconnect => "client_disconnect",
+            Self::ServerShutdown => "server_shutdown",
+            Self::Timeout => "timeout",
+            Self::Other(reason) => reason,
+        }
+    }
+}
+
+impl fmt::Display for CancellationReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for CancellationReason {
+    fn from(reason: String) -> Self {
+        match reason.as_str() {
+            "client_disconnect" => Self::ClientDisconnect,
+            "server_shutdown" => Self::ServerShutdown,
+            "timeout" => Self::Timeout,
+            _ => Self::Other(reason),
+        }
+    }
+}
+
+impl From<CancellationReason> for String {
+    fn from(reason: CancellationReason) -> Self {
+        match reason {
+            CancellationReason::Other(reason) => reason,
+            known => known.as_str().to_string(),
         }
     }
 }
 
 // ---------------------------------------------------------------------------
-// CanonicalError Enum
+// Context equality
 // ---------------------------------------------------------------------------
+//
+// The `gts_type` marker is excluded: deserialized contexts carry the
+// `dummy_gts_schema_id()` placeholder, so comparing it would make a decoded
+// context unequal to the one it was encoded from.
 
-#[derive(Debug, Clone)]
-pub enum CanonicalError {
-    Cancelled {
-        ctx: RequestInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    Unknown {
-        ctx: DebugInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    InvalidArgument {
-        ctx: Validation,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    DeadlineExceeded {
-        ctx: RequestInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    NotFound {
-        ctx: ResourceInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    AlreadyExists {
-        ctx: ResourceInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    PermissionDenied {
-        ctx: ErrorInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    ResourceExhausted {
-        ctx: QuotaFailure,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    FailedPrecondition {
-        ctx: PreconditionFailure,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    Aborted {
-        ctx: ErrorInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
-    },
-    OutOfRange {
+impl PartialEq for FieldViolationV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.field == other.field
+            && self.description == other.description
+            && self.reason == other.reason
+    }
+}
+
+impl Eq for FieldViolationV1 {}
+
+impl PartialEq for ResourceInfoV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.resource_type == other.resource_type
+            && self.resource_name == other.resource_name
+            && self.description == other.description
+            && self.parent == other.parent
+    }
+}
+
+impl Eq for ResourceInfoV1 {}
+
+impl PartialEq for ErrorInfoV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.reason == other.reason
+            && self.domain == other.domain
+            && self.metadata == other.metadata
+    }
+}
+
+impl Eq for ErrorInfoV1 {}
+
+impl PartialEq for QuotaViolationV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.subject == other.subject
+            && self.description == other.description
+    }
+}
+
+impl Eq for QuotaViolationV1 {}
+
+impl PartialEq for QuotaFailureV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.violations == other.violations
+    }
+}
+
+impl Eq for QuotaFailureV1 {}
+
+impl PartialEq for PreconditionViolationV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.precondition_type == other.precondition_type
+            && self.subject == other.subject
+            && self.description == other.description
+            && self.subject_path == other.subject_path
+    }
+}
+
+impl Eq for PreconditionViolationV1 {}
+
+impl PartialEq for PreconditionFailureV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.violations == other.violations
+    }
+}
+
+impl Eq for PreconditionFailureV1 {}
+
+impl PartialEq for DebugInfoV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.detail == other.detail
+            && self.stack_entries == other.stack_entries
+    }
+}
+
+impl Eq for DebugInfoV1 {}
+
+impl PartialEq for RetryInfoV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.retry_after_seconds == other.retry_after_seconds
+            && self.retry_at == other.retry_at
+    }
+}
+
+impl Eq for RetryInfoV1 {}
+
+impl PartialEq for RequestInfoV1 {
+    fn eq(&self, other: &Self) -> bool {
+        self.request_id == other.request_id && self.elapsed_ms == other.elapsed_ms
+    }
+}
+
+impl Eq for RequestInfoV1 {}
+
+// ---------------------------------------------------------------------------
+// Context hashing (`hash` feature)
+// ---------------------------------------------------------------------------
+//
+// Each `Hash` impl covers exactly the fields its `PartialEq` compares, so
+// equal values always hash equally and `gts_type` is skipped here too.
+
+#[cfg(feature = "hash")]
+impl Hash for FieldViolationV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.field.hash(state);
+        self.description.hash(state);
+        self.reason.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for ResourceInfoV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.resource_type.hash(state);
+        self.resource_name.hash(state);
+        self.description.hash(state);
+        self.parent.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for ErrorInfoV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.reason.hash(state);
+        self.domain.hash(state);
+        self.metadata.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for QuotaViolationV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.subject.hash(state);
+        self.description.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for QuotaFailureV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.violations.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for PreconditionViolationV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.precondition_type.hash(state);
+        self.subject.hash(state);
+        self.description.hash(state);
+        self.subject_path.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for PreconditionFailureV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.violations.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for DebugInfoV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.detail.hash(state);
+        self.stack_entries.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for RetryInfoV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.retry_after_seconds.hash(state);
+        self.retry_at.hash(state);
+    }
+}
+
+#[cfg(feature = "hash")]
+impl Hash for RequestInfoV1 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.request_id.hash(state);
+        self.elapsed_ms.hash(state);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Category
+// ---------------------------------------------------------------------------
+
+/// The canonical error category, without its context payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Cancelled,
+    Unknown,
+    InvalidArgument,
+    DeadlineExceeded,
+    NotFound,
+    AlreadyExists,
+    PermissionDenied,
+    ResourceExhausted,
+    FailedPrecondition,
+    Aborted,
+    OutOfRange,
+    Unimplemented,
+    Internal,
+    ServiceUnavailable,
+    DataLoss,
+    Unauthenticated,
+}
+
+impl Category {
+    /// Every category, in gRPC code order.
+    pub const ALL: [Category; 16] = [
+        Self::Cancelled,
+        Self::Unknown,
+        Self::InvalidArgument,
+        Self::DeadlineExceeded,
+        Self::NotFound,
+        Self::AlreadyExists,
+        Self::PermissionDenied,
+        Self::ResourceExhausted,
+        Self::FailedPrecondition,
+        Self::Aborted,
+        Self::OutOfRange,
+        Self::Unimplemented,
+        Self::Internal,
+        Self::ServiceUnavailable,
+        Self::DataLoss,
+        Self::Unauthenticated,
+    ];
+
+    pub fn gts_type(self) -> &'static str {
+        match self {
+            Self::Cancelled => "gts.cf.core.errors.err.v1~cf.core.errors.cancelled.v1~",
+            Self::Unknown => "gts.cf.core.errors.err.v1~cf.core.errors.unknown.v1~",
+            Self::InvalidArgument => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.invalid_argument.v1~"
+            }
+            Self::DeadlineExceeded => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.deadline_exceeded.v1~"
+            }
+            Self::NotFound => "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+            Self::AlreadyExists => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.already_exists.v1~"
+            }
+            Self::PermissionDenied => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.permission_denied.v1~"
+            }
+            Self::ResourceExhausted => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.resource_exhausted.v1~"
+            }
+            Self::FailedPrecondition => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.failed_precondition.v1~"
+            }
+            Self::Aborted => "gts.cf.core.errors.err.v1~cf.core.errors.aborted.v1~",
+            Self::OutOfRange => "gts.cf.core.errors.err.v1~cf.core.errors.out_of_range.v1~",
+            Self::Unimplemented => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.unimplemented.v1~"
+            }
+            Self::Internal => "gts.cf.core.errors.err.v1~cf.core.errors.internal.v1~",
+            Self::ServiceUnavailable => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.service_unavailable.v1~"
+            }
+            Self::DataLoss => "gts.cf.core.errors.err.v1~cf.core.errors.data_loss.v1~",
+            Self::Unauthenticated => {
+                "gts.cf.core.errors.err.v1~cf.core.errors.unauthenticated.v1~"
+            }
+        }
+    }
+
+    // --- Canonical HTTP status codes, usable as match patterns ---
+
+    pub const CANCELLED_STATUS: u16 = 499;
+    pub const UNKNOWN_STATUS: u16 = 500;
+    pub const INVALID_ARGUMENT_STATUS: u16 = 400;
+    pub const DEADLINE_EXCEEDED_STATUS: u16 = 504;
+    pub const NOT_FOUND_STATUS: u16 = 404;
+    pub const ALREADY_EXISTS_STATUS: u16 = 409;
+    pub const PERMISSION_DENIED_STATUS: u16 = 403;
+    pub const RESOURCE_EXHAUSTED_STATUS: u16 = 429;
+    pub const FAILED_PRECONDITION_STATUS: u16 = 400;
+    pub const ABORTED_STATUS: u16 = 409;
+    pub const OUT_OF_RANGE_STATUS: u16 = 400;
+    pub const UNIMPLEMENTED_STATUS: u16 = 501;
+    pub const INTERNAL_STATUS: u16 = 500;
+    pub const SERVICE_UNAVAILABLE_STATUS: u16 = 503;
+    pub const DATA_LOSS_STATUS: u16 = 500;
+    pub const UNAUTHENTICATED_STATUS: u16 = 401;
+
+    /// Returns the canonical HTTP status code for this category.
+    pub fn status_code(self) -> u16 {
+        match self {
+            Self::Cancelled => Self::CANCELLED_STATUS,
+            Self::Unknown => Self::UNKNOWN_STATUS,
+            Self::InvalidArgument => Self::INVALID_ARGUMENT_STATUS,
+            Self::DeadlineExceeded => Self::DEADLINE_EXCEEDED_STATUS,
+            Self::NotFound => Self::NOT_FOUND_STATUS,
+            Self::AlreadyExists => Self::ALREADY_EXISTS_STATUS,
+            Self::PermissionDenied => Self::PERMISSION_DENIED_STATUS,
+            Self::ResourceExhausted => Self::RESOURCE_EXHAUSTED_STATUS,
+            Self::FailedPrecondition => Self::FAILED_PRECONDITION_STATUS,
+            Self::Aborted => Self::ABORTED_STATUS,
+            Self::OutOfRange => Self::OUT_OF_RANGE_STATUS,
+            Self::Unimplemented => Self::UNIMPLEMENTED_STATUS,
+            Self::Internal => Self::INTERNAL_STATUS,
+            Self::ServiceUnavailable => Self::SERVICE_UNAVAILABLE_STATUS,
+            Self::DataLoss => Self::DATA_LOSS_STATUS,
+            Self::Unauthenticated => Self::UNAUTHENTICATED_STATUS,
+        }
+    }
+
+    /// Returns the canonical gRPC status code (`google.rpc.Code`) for this category.
+    ///
+    /// `0` (`OK`) is never returned since it does not represent an error.
+    pub fn grpc_code(self) -> i32 {
+        match self {
+            Self::Cancelled => 1,           // CANCELLED
+            Self::Unknown => 2,             // UNKNOWN
+            Self::InvalidArgument => 3,     // INVALID_ARGUMENT
+            Self::DeadlineExceeded => 4,    // DEADLINE_EXCEEDED
+            Self::NotFound => 5,            // NOT_FOUND
+            Self::AlreadyExists => 6,       // ALREADY_EXISTS
+            Self::PermissionDenied => 7,    // PERMISSION_DENIED
+            Self::ResourceExhausted => 8,   // RESOURCE_EXHAUSTED
+            Self::FailedPrecondition => 9,  // FAILED_PRECONDITION
+            Self::Aborted => 10,            // ABORTED
+            Self::OutOfRange => 11,         // OUT_OF_RANGE
+            Self::Unimplemented => 12,      // UNIMPLEMENTED
+            Self::Internal => 13,           // INTERNAL
+            Self::ServiceUnavailable => 14, // UNAVAILABLE
+            Self::DataLoss => 15,           // DATA_LOSS
+            Self::Unauthenticated => 16,    // UNAUTHENTICATED
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Cancelled => "Cancelled",
+            Self::Unknown => "Unknown",
+            Self::InvalidArgument => "Invalid Argument",
+            Self::DeadlineExceeded => "Deadline Exceeded",
+            Self::NotFound => "Not Found",
+            Self::AlreadyExists => "Already Exists",
+            Self::PermissionDenied => "Permission Denied",
+            Self::ResourceExhausted => "Resource Exhausted",
+            Self::FailedPrecondition => "Failed Precondition",
+            Self::Aborted => "Aborted",
+            Self::OutOfRange => "Out of Range",
+            Self::Unimplemented => "Unimplemented",
+            Self::Internal => "Internal",
+            Self::ServiceUnavailable => "Unavailable",
+            Self::DataLoss => "Data Loss",
+            Self::Unauthenticated => "Unauthenticated",
+        }
+    }
+
+    /// The constructor's default message, or a generic one for categories
+    /// whose constructors derive the message from the context.
+    pub fn default_message(self) -> &'static str {
+        match self {
+            Self::Cancelled => "Operation cancelled by the client",
+            Self::Unknown => "An unknown error occurred",
+            Self::InvalidArgument => "Request validation failed",
+            Self::DeadlineExceeded => "Operation did not complete within the allowed time",
+            Self::NotFound => "Resource not found",
+            Self::AlreadyExists => "Resource already exists",
+            Self::PermissionDenied => "You do not have permission to perform this operation",
+            Self::ResourceExhausted => "Quota exceeded",
+            Self::FailedPrecondition => "Operation precondition not met",
+            Self::Aborted => "Operation aborted due to concurrency conflict",
+            Self::OutOfRange => "Value out of range",
+            Self::Unimplemented => "This operation is not implemented",
+            Self::Internal => "An internal error occurred. Please retry later.",
+            Self::ServiceUnavailable => "Service temporarily unavailable",
+            Self::DataLoss => "Data loss detected",
+            Self::Unauthenticated => "Authentication required",
+        }
+    }
+
+    /// The snake_case category name, as used in `Problem.code`, the tagged
+    /// serde form, `Display` and the GTS type segment
+    /// (`...~cf.core.errors.<name>.v1~`). `ServiceUnavailable` is spelled
+    /// `service_unavailable` everywhere.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Cancelled => "cancelled",
+            Self::Unknown => "unknown",
+            Self::InvalidArgument => "invalid_argument",
+            Self::DeadlineExceeded => "deadline_exceeded",
+            Self::NotFound => "not_found",
+            Self::AlreadyExists => "already_exists",
+            Self::PermissionDenied => "permission_denied",
+            Self::ResourceExhausted => "resource_exhausted",
+            Self::FailedPrecondition => "failed_precondition",
+            Self::Aborted => "aborted",
+            Self::OutOfRange => "out_of_range",
+            Self::Unimplemented => "unimplemented",
+            Self::Internal => "internal",
+            Self::ServiceUnavailable => "service_unavailable",
+            Self::DataLoss => "data_loss",
+            Self::Unauthenticated => "unauthenticated",
+        }
+    }
+
+    /// Inverse of [`Category::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| category.name() == name)
+    }
+}
+
+/// Inverse of [`Category::gts_type`]: maps a `Problem.type` value back to its
+/// category. Returns `None` for anything that is not exactly one of the 16
+/// canonical type ids.
+pub fn lookup_category_by_gts_type(gts_type: &str) -> Option<Category> {
+    Category::ALL.into_iter().find(|category| category.gts_type() == gts_type)
+}
+
+/// Every category whose [`Category::status_code`] is `status`, in gRPC code
+/// order. Several categories share a status (400, 409 and 500), so use
+/// [`lookup_category_by_gts_type`] when the `type` is available.
+pub fn lookup_by_status(status: u16) -> Vec<Category> {
+    Category::ALL
+        .into_iter()
+        .filter(|category| category.status_code() == status)
+        .collect()
+}
+
+/// A borrowed context payload, see [`CanonicalError::context`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum ErrorContext<'a> {
+    RequestInfo(&'a RequestInfo),
+    DebugInfo(&'a DebugInfo),
+    Validation(&'a Validation),
+    ResourceInfo(&'a ResourceInfo),
+    ErrorInfo(&'a ErrorInfo),
+    QuotaFailure(&'a QuotaFailure),
+    PreconditionFailure(&'a PreconditionFailure),
+    RetryInfo(&'a RetryInfo),
+}
+
+/// Supplies default messages, e.g. per locale, in place of the built-in
+/// English ones. See [`CanonicalError::with_message_from`].
+pub trait MessageProvider {
+    /// Returns `None` to keep the built-in English default.
+    fn default_message(&self, category: Category, ctx: &ErrorContext<'_>) -> Option<String>;
+}
+
+/// An owned context payload, one variant per context type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "hash", derive(Hash))]
+pub enum OwnedErrorContext {
+    RequestInfo(RequestInfo),
+    DebugInfo(DebugInfo),
+    Validation(Validation),
+    ResourceInfo(ResourceInfo),
+    ErrorInfo(ErrorInfo),
+    QuotaFailure(QuotaFailure),
+    PreconditionFailure(PreconditionFailure),
+    RetryInfo(RetryInfo),
+}
+
+/// The lower-level error a [`CanonicalError`] was built from, see
+/// [`CanonicalError::with_source`]. Shared so the error stays `Clone`;
+/// sources compare equal when their `Display` output matches.
+#[derive(Debug, Clone)]
+pub struct ErrorSource(Arc<dyn std::error::Error + Send + Sync>);
+
+impl ErrorSource {
+    pub fn new(err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self(Arc::new(err))
+    }
+
+    pub fn get(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        &*self.0
+    }
+}
+
+impl From<Box<dyn std::error::Error + Send + Sync>> for ErrorSource {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        Self(Arc::from(err))
+    }
+}
+
+impl PartialEq for ErrorSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0) || self.0.to_string() == other.0.to_string()
+    }
+}
+
+impl Eq for ErrorSource {}
+
+/// Hashes the `Display` text, which equal sources always share.
+#[cfg(feature = "hash")]
+impl Hash for ErrorSource {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_string().hash(state);
+    }
+}
+
+/// Fields every [`CanonicalError`] variant carries next to its typed
+/// context, see [`CanonicalError::meta`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorMeta {
+    pub message: String,
+    pub resource_type: Option<String>,
+    pub debug_info: Vec<DebugInfo>,
+    /// Underlying cause, see [`CanonicalError::with_source`].
+    pub source: Option<ErrorSource>,
+    /// Per-locale message overrides, see [`CanonicalError::localize`].
+    pub localized_messages: HashMap<String, String>,
+    /// RFC 9457 `instance`, see [`CanonicalError::with_instance`].
+    pub instance: Option<String>,
+    /// Correlation id, see [`CanonicalError::with_trace_id`].
+    pub trace_id: Option<String>,
+    /// Symbolic error code, see [`CanonicalError::with_code`].
+    pub code: Option<String>,
+}
+
+impl ErrorMeta {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Owned components of a [`CanonicalError`], see [`CanonicalError::into_parts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorParts {
+    pub category: Category,
+    pub context: OwnedErrorContext,
+    pub meta: ErrorMeta,
+    /// Per-instance HTTP status override; only `Unknown` carries one.
+    pub status: Option<u16>,
+    /// Why the operation was cancelled; only `Cancelled` carries one.
+    pub cancellation_reason: Option<CancellationReason>,
+}
+
+// ---------------------------------------------------------------------------
+// CanonicalError Enum
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CanonicalError {
+    Cancelled {
+        ctx: RequestInfo,
+        meta: ErrorMeta,
+        /// Set by [`CanonicalError::cancelled_with_reason`].
+        reason: Option<CancellationReason>,
+    },
+    Unknown {
+        ctx: DebugInfo,
+        meta: ErrorMeta,
+        /// Upstream HTTP status preserved when degrading an unrecognized error.
+        status: Option<u16>,
+    },
+    InvalidArgument {
+        ctx: Validation,
+        meta: ErrorMeta,
+    },
+    DeadlineExceeded {
+        ctx: RequestInfo,
+        meta: ErrorMeta,
+    },
+    NotFound {
+        ctx: ResourceInfo,
+        meta: ErrorMeta,
+    },
+    AlreadyExists {
+        ctx: ResourceInfo,
+        meta: ErrorMeta,
+    },
+    PermissionDenied {
+        ctx: ErrorInfo,
+        meta: ErrorMeta,
+    },
+    ResourceExhausted {
+        ctx: QuotaFailure,
+        meta: ErrorMeta,
+    },
+    FailedPrecondition {
+        ctx: PreconditionFailure,
+        meta: ErrorMeta,
+    },
+    Aborted {
+        ctx: ErrorInfo,
+        meta: ErrorMeta,
+    },
+    OutOfRange {
         ctx: Validation,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
     Unimplemented {
         ctx: ErrorInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
     Internal {
         ctx: DebugInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
     ServiceUnavailable {
         ctx: RetryInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
     DataLoss {
         ctx: ResourceInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
     Unauthenticated {
         ctx: ErrorInfo,
-        message: String,
-        resource_type: Option<String>,
-        debug_info: Option<DebugInfo>,
+        meta: ErrorMeta,
     },
 }
 
@@ -507,9 +1589,23 @@ impl CanonicalError {
     pub fn cancelled(ctx: RequestInfo) -> Self {
         Self::Cancelled {
             ctx,
-            message: String::from("Operation cancelled by the client"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::Cancelled.default_message()),
+            reason: None,
+        }
+    }
+
+    /// Like [`cancelled`](Self::cancelled), with the message
+    /// `"Operation cancelled: {reason}"` so shutdown-induced cancellations can
+    /// be told apart from client disconnects. Pass a [`CancellationReason`]
+    /// for the well-known cases; [`cancellation_reason`](Self::cancellation_reason)
+    /// returns it.
+    pub fn cancelled_with_reason(ctx: RequestInfo, reason: impl Into<String>) -> Self {
+        let reason = CancellationReason::from(reason.into());
+        let message = format!("Operation cancelled: {reason}");
+        Self::Cancelled {
+            ctx,
+            meta: ErrorMeta::new(message),
+            reason: Some(reason),
         }
     }
 
@@ -518,128 +1614,178 @@ impl CanonicalError {
         let message = detail.clone();
         Self::Unknown {
             ctx: DebugInfo::new(detail),
-            message,
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(message),
+            status: None,
+        }
+    }
+
+    /// Builds an `Unknown` error that reports `status` instead of 500, for
+    /// degrading an unrecognized upstream error while keeping its HTTP code.
+    /// Only error statuses (400..=599) are kept; anything else (e.g. 200 or
+    /// 42) falls back to 500.
+    pub fn unknown_with_status(detail: impl Into<String>, status: u16) -> Self {
+        let mut err = Self::unknown(detail);
+        if let Self::Unknown { status: s, .. } = &mut err
+            && (400..=599).contains(&status)
+        {
+            *s = Some(status);
         }
+        err
     }
 
     pub fn invalid_argument(ctx: Validation) -> Self {
         let message = match &ctx {
-            Validation::FieldViolations { .. } => String::from("Request validation failed"),
+            Validation::FieldViolations { .. } => String::from(Category::InvalidArgument.default_message()),
             Validation::Format { format } => format.clone(),
             Validation::Constraint { constraint } => constraint.clone(),
         };
         Self::InvalidArgument {
             ctx,
-            message,
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(message),
+        }
+    }
+
+    /// `InvalidArgument` over `violations`, or `None` when there are none, so
+    /// the collected violations can be returned with
+    /// `if let Some(err) = ... { return Err(err) }`.
+    pub fn invalid_argument_fields(violations: impl Into<Vec<FieldViolation>>) -> Option<Self> {
+        let violations = violations.into();
+        if violations.is_empty() {
+            return None;
         }
+        Some(Self::invalid_argument(Validation::fields(violations)))
+    }
+
+    /// Shorthand for an `InvalidArgument` carrying a single field violation.
+    /// Prefer this over the `ParseIntError`/`ParseFloatError` conversions
+    /// when the field name is known, since those can only report a format
+    /// error.
+    pub fn invalid_field(
+        field: impl Into<String>,
+        description: impl Into<String>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::invalid_argument(Validation::fields(vec![FieldViolation::new(field, description, reason)]))
     }
 
     pub fn deadline_exceeded(ctx: RequestInfo) -> Self {
         Self::DeadlineExceeded {
             ctx,
-            message: String::from("Operation did not complete within the allowed time"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::DeadlineExceeded.default_message()),
         }
     }
 
+    /// Like [`CanonicalError::deadline_exceeded`], recording how long the
+    /// operation waited in `RequestInfo.elapsed_ms` (saturating at `u64::MAX`).
+    pub fn deadline_exceeded_after(mut ctx: RequestInfo, elapsed: std::time::Duration) -> Self {
+        ctx.elapsed_ms = Some(u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX));
+        Self::deadline_exceeded(ctx)
+    }
+
     pub fn not_found(ctx: ResourceInfo) -> Self {
         Self::NotFound {
             ctx,
-            message: String::from("Resource not found"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::NotFound.default_message()),
         }
     }
 
+    /// Shorthand for `not_found(ResourceInfo::new(resource_type, resource_name))`
+    /// with `resource_type` also set on the error.
+    pub fn not_found_resource(resource_type: impl Into<String>, resource_name: impl Into<String>) -> Self {
+        let resource_type = resource_type.into();
+        Self::not_found(ResourceInfo::new(resource_type.clone(), resource_name)).with_resource_type(resource_type)
+    }
+
     pub fn already_exists(ctx: ResourceInfo) -> Self {
         let message = ctx.description.clone();
         Self::AlreadyExists {
             ctx,
-            message,
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(message),
         }
     }
 
+    /// Like [`CanonicalError::not_found_resource`], for `already_exists`.
+    pub fn already_exists_resource(resource_type: impl Into<String>, resource_name: impl Into<String>) -> Self {
+        let resource_type = resource_type.into();
+        Self::already_exists(ResourceInfo::new(resource_type.clone(), resource_name)).with_resource_type(resource_type)
+    }
+
     pub fn permission_denied(ctx: ErrorInfo) -> Self {
         Self::PermissionDenied {
             ctx,
-            message: String::from("You do not have permission to perform this operation"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::PermissionDenied.default_message()),
         }
     }
 
     pub fn resource_exhausted(ctx: QuotaFailure) -> Self {
         Self::ResourceExhausted {
             ctx,
-            message: String::from("Quota exceeded"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::ResourceExhausted.default_message()),
         }
     }
 
     pub fn failed_precondition(ctx: PreconditionFailure) -> Self {
         Self::FailedPrecondition {
             ctx,
-            message: String::from("Operation precondition not met"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::FailedPrecondition.default_message()),
         }
     }
 
     pub fn aborted(ctx: ErrorInfo) -> Self {
         Self::Aborted {
             ctx,
-            message: String::from("Operation aborted due to concurrency conflict"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::Aborted.default_message()),
         }
     }
 
     pub fn out_of_range(ctx: Validation) -> Self {
         let message = match &ctx {
-            Validation::FieldViolations { .. } => String::from("Value out of range"),
+            Validation::FieldViolations { .. } => String::from(Category::OutOfRange.default_message()),
             Validation::Format { format } => format.clone(),
             Validation::Constraint { constraint } => constraint.clone(),
         };
         Self::OutOfRange {
             ctx,
-            message,
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(message),
         }
     }
 
     pub fn unimplemented(ctx: ErrorInfo) -> Self {
         Self::Unimplemented {
             ctx,
-            message: String::from("This operation is not implemented"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::Unimplemented.default_message()),
         }
     }
 
+    /// `ctx` is the error's context and is always serialized into the public
+    /// `context` body, even by [`Problem::from_error`]. Attach anything that
+    /// must stay out of production responses with
+    /// [`with_debug_info`](Self::with_debug_info) instead.
     pub fn internal(ctx: DebugInfo) -> Self {
         Self::Internal {
             ctx,
-            message: String::from("An internal error occurred. Please retry later."),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::Internal.default_message()),
         }
     }
 
+    /// Like [`internal`](Self::internal), but with a caller-supplied public
+    /// message instead of the generic one.
+    pub fn internal_with_message(message: impl Into<String>, ctx: DebugInfo) -> Self {
+        Self::internal(ctx).with_message(message)
+    }
+
+    /// Reports a panic caught with `catch_unwind`. Panic messages often
+    /// carry internal state, so the public context stays empty and the
+    /// [`DebugInfo::from_panic`] description is attached with
+    /// [`with_debug_info`](Self::with_debug_info).
+    pub fn internal_from_panic(payload: &(dyn std::any::Any + Send)) -> Self {
+        Self::with_empty_context(Category::Internal).with_debug_info(DebugInfo::from_panic(payload))
+    }
+
     pub fn service_unavailable(ctx: RetryInfo) -> Self {
         Self::ServiceUnavailable {
             ctx,
-            message: String::from("Service temporarily unavailable"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::ServiceUnavailable.default_message()),
         }
     }
 
@@ -647,18 +1793,103 @@ impl CanonicalError {
         let message = ctx.description.clone();
         Self::DataLoss {
             ctx,
-            message,
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(message),
         }
     }
 
+    /// Like [`CanonicalError::not_found_resource`], for `data_loss`.
+    pub fn data_loss_resource(resource_type: impl Into<String>, resource_name: impl Into<String>) -> Self {
+        let resource_type = resource_type.into();
+        Self::data_loss(ResourceInfo::new(resource_type.clone(), resource_name)).with_resource_type(resource_type)
+    }
+
     pub fn unauthenticated(ctx: ErrorInfo) -> Self {
         Self::Unauthenticated {
             ctx,
-            message: String::from("Authentication required"),
-            resource_type: None,
-            debug_info: None,
+            meta: ErrorMeta::new(Category::Unauthenticated.default_message()),
+        }
+    }
+
+    /// Lifts an upstream HTTP status into the nearest canonical category,
+    /// using `detail` as the message:
+    ///
+    /// - 400 → `InvalidArgument` (not `FailedPrecondition`/`OutOfRange`)
+    /// - 401 → `Unauthenticated`, 403 → `PermissionDenied`, 404 → `NotFound`
+    /// - 409 → `AlreadyExists` (not `Aborted`)
+    /// - 429 → `ResourceExhausted`
+    /// - 503 → `ServiceUnavailable`, 504 → `DeadlineExceeded`
+    /// - any other 5xx → `Internal`
+    /// - anything else → `Unknown`, keeping a 4xx `code` as its status
+    pub fn from_status_code(code: u16, detail: impl Into<String>) -> Self {
+        let detail = detail.into();
+        let http_error_info = || ErrorInfo::new(format!("HTTP_{code}"), "http");
+        let err = match code {
+            400 => Self::invalid_argument(Validation::constraint(detail.clone())),
+            401 => Self::unauthenticated(http_error_info()),
+            403 => Self::permission_denied(http_error_info()),
+            404 => Self::with_empty_context(Category::NotFound),
+            409 => Self::with_empty_context(Category::AlreadyExists),
+            429 => Self::with_empty_context(Category::ResourceExhausted),
+            503 => Self::with_empty_context(Category::ServiceUnavailable),
+            504 => Self::with_empty_context(Category::DeadlineExceeded),
+            500..=599 => Self::internal(DebugInfo::new(detail.clone())),
+            _ => return Self::unknown_with_status(detail, code),
+        };
+        err.with_message(detail)
+    }
+
+    /// Builds an error of `category` from any `Display` value, so a
+    /// downstream error enum can route a source to a category in one line.
+    /// The error keeps the category's default message and an empty context;
+    /// the display text, which may be driver output, is only recorded with
+    /// [`add_debug_info`](Self::add_debug_info).
+    ///
+    /// With thiserror, wrap the canonical error with `#[from]` and map other
+    /// sources explicitly:
+    ///
+    /// ```ignore
+    /// #[derive(Debug, thiserror::Error)]
+    /// enum RepoError {
+    ///     #[error(transparent)]
+    ///     Canonical(#[from] CanonicalError),
+    ///     #[error("database error: {0}")]
+    ///     Db(#[from] sqlx::Error),
+    /// }
+    ///
+    /// impl From<RepoError> for CanonicalError {
+    ///     fn from(err: RepoError) -> Self {
+    ///         match err {
+    ///             RepoError::Canonical(err) => err,
+    ///             RepoError::Db(err) => CanonicalError::from_display(Category::ServiceUnavailable, err),
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn from_display<E: fmt::Display>(category: Category, e: E) -> Self {
+        Self::with_empty_context(category).add_debug_info(DebugInfo::new(e.to_string()))
+    }
+
+    /// An error of `category` with an empty context (e.g.
+    /// `ResourceInfo::new("", "")`) and the category's default message.
+    fn with_empty_context(category: Category) -> Self {
+        let empty_resource = || ResourceInfo::new("", "").with_description(category.default_message());
+        match category {
+            Category::Cancelled => Self::cancelled(RequestInfo::new("")),
+            Category::Unknown => Self::unknown("").with_message(category.default_message()),
+            Category::InvalidArgument => Self::invalid_argument(Validation::fields(vec![])),
+            Category::DeadlineExceeded => Self::deadline_exceeded(RequestInfo::new("")),
+            Category::NotFound => Self::not_found(empty_resource()),
+            Category::AlreadyExists => Self::already_exists(empty_resource()),
+            Category::PermissionDenied => Self::permission_denied(ErrorInfo::new("", "")),
+            Category::ResourceExhausted => Self::resource_exhausted(QuotaFailure::new(vec![])),
+            Category::FailedPrecondition => Self::failed_precondition(PreconditionFailure::new(vec![])),
+            Category::Aborted => Self::aborted(ErrorInfo::new("", "")),
+            Category::OutOfRange => Self::out_of_range(Validation::fields(vec![])),
+            Category::Unimplemented => Self::unimplemented(ErrorInfo::new("", "")),
+            Category::Internal => Self::internal(DebugInfo::new("")),
+            Category::ServiceUnavailable => Self::service_unavailable(RetryInfo::unspecified()),
+            Category::DataLoss => Self::data_loss(empty_resource()),
+            Category::Unauthenticated => Self::unauthenticated(ErrorInfo::new("", "")),
         }
     }
 
@@ -666,615 +1897,4382 @@ impl CanonicalError {
 
     pub fn with_message(mut self, msg: impl Into<String>) -> Self {
         let msg = msg.into();
-        match &mut self {
-            Self::Cancelled { message, .. }
-            | Self::Unknown { message, .. }
-            | Self::InvalidArgument { message, .. }
-            | Self::DeadlineExceeded { message, .. }
-            | Self::NotFound { message, .. }
-            | Self::AlreadyExists { message, .. }
-            | Self::PermissionDenied { message, .. }
-            | Self::ResourceExhausted { message, .. }
-            | Self::FailedPrecondition { message, .. }
-            | Self::Aborted { message, .. }
-            | Self::OutOfRange { message, .. }
-            | Self::Unimplemented { message, .. }
-            | Self::Internal { message, .. }
-            | Self::ServiceUnavailable { message, .. }
-            | Self::DataLoss { message, .. }
-            | Self::Unauthenticated { message, .. } => *message = msg,
-        }
+        self.meta_mut().message = msg;
         self
     }
 
+    /// Tags the error with a resource type. The tag may legally differ from a
+    /// `ResourceInfo.resource_type`; use [`resource_type_mismatch`](Self::resource_type_mismatch)
+    /// to detect and warn about that.
     pub fn with_resource_type(mut self, rt: impl Into<String>) -> Self {
         let rt = Some(rt.into());
-        match &mut self {
-            Self::Cancelled { resource_type, .. }
-            | Self::Unknown { resource_type, .. }
-            | Self::InvalidArgument { resource_type, .. }
-            | Self::DeadlineExceeded { resource_type, .. }
-            | Self::NotFound { resource_type, .. }
-            | Self::AlreadyExists { resource_type, .. }
-            | Self::PermissionDenied { resource_type, .. }
-            | Self::ResourceExhausted { resource_type, .. }
-            | Self::FailedPrecondition { resource_type, .. }
-            | Self::Aborted { resource_type, .. }
-            | Self::OutOfRange { resource_type, .. }
-            | Self::Unimplemented { resource_type, .. }
-            | Self::Internal { resource_type, .. }
-            | Self::ServiceUnavailable { resource_type, .. }
-            | Self::DataLoss { resource_type, .. }
-            | Self::Unauthenticated { resource_type, .. } => *resource_type = rt,
-        }
+        self.meta_mut().resource_type = rt;
         self
     }
 
+    /// Attaches redactable debug details, emitted as `"debug"` only by
+    /// [`Problem::from_error_debug`]. For `Internal` and `Unknown` this is
+    /// separate from the context `DebugInfo`, which is always public.
     pub fn with_debug_info(mut self, info: DebugInfo) -> Self {
-        match &mut self {
-            Self::Cancelled { debug_info, .. }
-            | Self::Unknown { debug_info, .. }
-            | Self::InvalidArgument { debug_info, .. }
-            | Self::DeadlineExceeded { debug_info, .. }
-            | Self::NotFound { debug_info, .. }
-            | Self::AlreadyExists { debug_info, .. }
-            | Self::PermissionDenied { debug_info, .. }
-            | Self::ResourceExhausted { debug_info, .. }
-            | Self::FailedPrecondition { debug_info, .. }
-            | Self::Aborted { debug_info, .. }
-            | Self::OutOfRange { debug_info, .. }
-            | Self::Unimplemented { debug_info, .. }
-            | Self::Internal { debug_info, .. }
-            | Self::ServiceUnavailable { debug_info, .. }
-            | Self::DataLoss { debug_info, .. }
-            | Self::Unauthenticated { debug_info, .. } => *debug_info = Some(info),
-        }
+        self.meta_mut().debug_info = vec![info];
         self
     }
 
-    // --- Accessors ---
+    /// Appends `info` after any debug info already attached, e.g. one entry
+    /// per layer (DB, cache, handler).
+    pub fn add_debug_info(mut self, info: DebugInfo) -> Self {
+        self.meta_mut().debug_info.push(info);
+        self
+    }
 
-    pub fn message(&self) -> &str {
-        match self {
-            Self::Cancelled { message, .. }
-            | Self::Unknown { message, .. }
-            | Self::InvalidArgument { message, .. }
-            | Self::DeadlineExceeded { message, .. }
-            | Self::NotFound { message, .. }
-            | Self::AlreadyExists { message, .. }
-            | Self::PermissionDenied { message, .. }
-            | Self::ResourceExhausted { message, .. }
-            | Self::FailedPrecondition { message, .. }
-            | Self::Aborted { message, .. }
-            | Self::OutOfRange { message, .. }
-            | Self::Unimplemented { message, .. }
-            | Self::Internal { message, .. }
-            | Self::ServiceUnavailable { message, .. }
-            | Self::DataLoss { message, .. }
-            | Self::Unauthenticated { message, .. } => message,
+    /// Appends `entry` to the first `DebugInfo`'s stack, creating a
+    /// `DebugInfo` with an empty detail if the error has none.
+    pub fn push_stack_entry(&mut self, entry: impl Into<String>) {
+        let infos = &mut self.meta_mut().debug_info;
+        match infos.first_mut() {
+            Some(info) => info.push(entry),
+            None => infos.push(DebugInfo::new("").with_stack(vec![entry.into()])),
         }
     }
 
-    pub fn resource_type(&self) -> Option<&str> {
-        match self {
-            Self::Cancelled { resource_type, .. }
-            | Self::Unknown { resource_type, .. }
-            | Self::InvalidArgument { resource_type, .. }
-            | Self::DeadlineExceeded { resource_type, .. }
-            | Self::NotFound { resource_type, .. }
-            | Self::AlreadyExists { resource_type, .. }
-            | Self::PermissionDenied { resource_type, .. }
-            | Self::ResourceExhausted { resource_type, .. }
-            | Self::FailedPrecondition { resource_type, .. }
-            | Self::Aborted { resource_type, .. }
-            | Self::OutOfRange { resource_type, .. }
-            | Self::Unimplemented { resource_type, .. }
-            | Self::Internal { resource_type, .. }
-            | Self::ServiceUnavailable { resource_type, .. }
-            | Self::DataLoss { resource_type, .. }
-            | Self::Unauthenticated { resource_type, .. } => resource_type.as_deref(),
-        }
+    /// Attaches debug info only when `cond` is true; `f` is not called otherwise.
+    pub fn with_debug_info_if(self, cond: bool, f: impl FnOnce() -> DebugInfo) -> Self {
+        if cond { self.with_debug_info(f()) } else { self }
     }
 
-    pub fn debug_info(&self) -> Option<&DebugInfo> {
-        match self {
-            Self::Cancelled { debug_info, .. }
-            | Self::Unknown { debug_info, .. }
-            | Self::InvalidArgument { debug_info, .. }
-            | Self::DeadlineExceeded { debug_info, .. }
-            | Self::NotFound { debug_info, .. }
-            | Self::AlreadyExists { debug_info, .. }
-            | Self::PermissionDenied { debug_info, .. }
-            | Self::ResourceExhausted { debug_info, .. }
-            | Self::FailedPrecondition { debug_info, .. }
-            | Self::Aborted { debug_info, .. }
-            | Self::OutOfRange { debug_info, .. }
-            | Self::Unimplemented { debug_info, .. }
-            | Self::Internal { debug_info, .. }
-            | Self::ServiceUnavailable { debug_info, .. }
-            | Self::DataLoss { debug_info, .. }
-            | Self::Unauthenticated { debug_info, .. } => debug_info.as_ref(),
-        }
+    /// Records `err` as the cause: its `Display` text is appended as a new
+    /// `DebugInfo` (existing entries are kept) and
+    /// [`std::error::Error::source`] returns it.
+    pub fn with_source(self, err: impl std::error::Error + Send + Sync + 'static) -> Self {
+        let mut this = self.add_debug_info(DebugInfo::new(err.to_string()));
+        this.meta_mut().source = Some(ErrorSource::new(err));
+        this
     }
 
-    // --- GTS Catalog ---
+    /// Attaches `cause` as the [`std::error::Error::source`] without touching
+    /// the message or debug info. Replaces any previously attached cause.
+    pub fn with_cause(mut self, cause: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        self.meta_mut().source = Some(ErrorSource::from(cause.into()));
+        self
+    }
 
-    pub fn gts_type(&self) -> &'static str {
-        match self {
-            Self::Cancelled { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.cancelled.v1~",
-            Self::Unknown { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.unknown.v1~",
-            Self::InvalidArgument { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.invalid_argument.v1~"
-            }
-            Self::DeadlineExceeded { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.deadline_exceeded.v1~"
-            }
-            Self::NotFound { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
-            Self::AlreadyExists { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.already_exists.v1~"
-            }
-            Self::PermissionDenied { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.permission_denied.v1~"
-            }
-            Self::ResourceExhausted { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.resource_exhausted.v1~"
-            }
-            Self::FailedPrecondition { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.failed_precondition.v1~"
-            }
-            Self::Aborted { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.aborted.v1~",
-            Self::OutOfRange { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.out_of_range.v1~",
-            Self::Unimplemented { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.unimplemented.v1~"
-            }
-            Self::Internal { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.internal.v1~",
-            Self::ServiceUnavailable { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.service_unavailable.v1~"
-            }
-            Self::DataLoss { .. } => "gts.cf.core.errors.err.v1~cf.core.errors.data_loss.v1~",
-            Self::Unauthenticated { .. } => {
-                "gts.cf.core.errors.err.v1~cf.core.errors.unauthenticated.v1~"
-            }
-        }
+    /// Sets the RFC 9457 `instance` that [`Problem::from_error`] re-emits.
+    /// Populated by `TryFrom<Problem>`, so it survives a proxy hop.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.meta_mut().instance = Some(instance.into());
+        self
     }
 
-    pub fn status_code(&self) -> u16 {
-        match self {
-            Self::Cancelled { .. } => 499,
-            Self::Unknown { .. } => 500,
-            Self::InvalidArgument { .. } => 400,
-            Self::DeadlineExceeded { .. } => 504,
-            Self::NotFound { .. } => 404,
-            Self::AlreadyExists { .. } => 409,
-            Self::PermissionDenied { .. } => 403,
-            Self::ResourceExhausted { .. } => 429,
-            Self::FailedPrecondition { .. } => 400,
-            Self::Aborted { .. } => 409,
-            Self::OutOfRange { .. } => 400,
-            Self::Unimplemented { .. } => 501,
-            Self::Internal { .. } => 500,
-            Self::ServiceUnavailable { .. } => 503,
-            Self::DataLoss { .. } => 500,
-            Self::Unauthenticated { .. } => 401,
+    /// Sets the `trace_id` that [`Problem::from_error`] re-emits.
+    /// Populated by `TryFrom<Problem>`, so it survives a proxy hop.
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.meta_mut().trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Sets a short symbolic code (e.g. `USER_NOT_FOUND`) that
+    /// [`Problem::from_error`] emits as the top-level `error_code` member and
+    /// `TryFrom<Problem>` reads back.
+    ///
+    /// The code is not emitted as `code`: that member already carries the
+    /// category name and `TryFrom<Problem>` resolves the category from it, so
+    /// clients keying on the symbolic code must read `error_code`.
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.meta_mut().code = Some(code.into());
+        self
+    }
+
+    pub fn instance(&self) -> Option<&str> {
+        self.meta().instance.as_deref()
+    }
+
+    pub fn trace_id(&self) -> Option<&str> {
+        self.meta().trace_id.as_deref()
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.meta().code.as_deref()
+    }
+
+    /// Replaces the message with `provider`'s default for this category
+    /// and context; keeps the current message when the provider returns `None`.
+    pub fn with_message_from(self, provider: &(impl MessageProvider + ?Sized)) -> Self {
+        match provider.default_message(self.category(), &self.context()) {
+            Some(message) => self.with_message(message),
+            None => self,
         }
     }
 
-    pub fn title(&self) -> &'static str {
+    /// Sets a per-locale message override; an existing entry for `locale`
+    /// is replaced.
+    pub fn localize(mut self, locale: impl Into<String>, message: impl Into<String>) -> Self {
+        self.meta_mut().localized_messages.insert(locale.into(), message.into());
+        self
+    }
+
+    /// Shorthand for `Err(self)`, e.g. `return err.into_err();`.
+    #[allow(clippy::result_large_err)]
+    pub fn into_err<T>(self) -> Result<T, CanonicalError> {
+        Err(self)
+    }
+
+    /// Shorthand for `Err(Problem::from(self))`, e.g. `return err.into_problem_err();`.
+    #[allow(clippy::result_large_err)]
+    pub fn into_problem_err<T>(self) -> Result<T, Problem> {
+        Err(Problem::from(self))
+    }
+
+    // --- Accessors ---
+
+    /// The fields shared by every variant: message, resource type, debug
+    /// info, source, localized messages, instance, trace id and code.
+    pub fn meta(&self) -> &ErrorMeta {
         match self {
-            Self::Cancelled { .. } => "Cancelled",
-            Self::Unknown { .. } => "Unknown",
-            Self::InvalidArgument { .. } => "Invalid Argument",
-            Self::DeadlineExceeded { .. } => "Deadline Exceeded",
-            Self::NotFound { .. } => "Not Found",
-            Self::AlreadyExists { .. } => "Already Exists",
-            Self::PermissionDenied { .. } => "Permission Denied",
-            Self::ResourceExhausted { .. } => "Resource Exhausted",
-            Self::FailedPrecondition { .. } => "Failed Precondition",
-            Self::Aborted { .. } => "Aborted",
-            Self::OutOfRange { .. } => "Out of Range",
-            Self::Unimplemented { .. } => "Unimplemented",
-            Self::Internal { .. } => "Internal",
-            Self::ServiceUnavailable { .. } => "Unavailable",
-            Self::DataLoss { .. } => "Data Loss",
-            Self::Unauthenticated { .. } => "Unauthenticated",
+            Self::Cancelled { meta, .. }
+            | Self::Unknown { meta, .. }
+            | Self::InvalidArgument { meta, .. }
+            | Self::DeadlineExceeded { meta, .. }
+            | Self::NotFound { meta, .. }
+            | Self::AlreadyExists { meta, .. }
+            | Self::PermissionDenied { meta, .. }
+            | Self::ResourceExhausted { meta, .. }
+            | Self::FailedPrecondition { meta, .. }
+            | Self::Aborted { meta, .. }
+            | Self::OutOfRange { meta, .. }
+            | Self::Unimplemented { meta, .. }
+            | Self::Internal { meta, .. }
+            | Self::ServiceUnavailable { meta, .. }
+            | Self::DataLoss { meta, .. }
+            | Self::Unauthenticated { meta, .. } => meta,
         }
     }
 
-    fn category_name(&self) -> &'static str {
+    pub fn meta_mut(&mut self) -> &mut ErrorMeta {
         match self {
-            Self::Cancelled { .. } => "cancelled",
-            Self::Unknown { .. } => "unknown",
-            Self::InvalidArgument { .. } => "invalid_argument",
-            Self::DeadlineExceeded { .. } => "deadline_exceeded",
-            Self::NotFound { .. } => "not_found",
-            Self::AlreadyExists { .. } => "already_exists",
-            Self::PermissionDenied { .. } => "permission_denied",
-            Self::ResourceExhausted { .. } => "resource_exhausted",
-            Self::FailedPrecondition { .. } => "failed_precondition",
-            Self::Aborted { .. } => "aborted",
-            Self::OutOfRange { .. } => "out_of_range",
-            Self::Unimplemented { .. } => "unimplemented",
-            Self::Internal { .. } => "internal",
-            Self::ServiceUnavailable { .. } => "unavailable",
-            Self::DataLoss { .. } => "data_loss",
-            Self::Unauthenticated { .. } => "unauthenticated",
+            Self::Cancelled { meta, .. }
+            | Self::Unknown { meta, .. }
+            | Self::InvalidArgument { meta, .. }
+            | Self::DeadlineExceeded { meta, .. }
+            | Self::NotFound { meta, .. }
+            | Self::AlreadyExists { meta, .. }
+            | Self::PermissionDenied { meta, .. }
+            | Self::ResourceExhausted { meta, .. }
+            | Self::FailedPrecondition { meta, .. }
+            | Self::Aborted { meta, .. }
+            | Self::OutOfRange { meta, .. }
+            | Self::Unimplemented { meta, .. }
+            | Self::Internal { meta, .. }
+            | Self::ServiceUnavailable { meta, .. }
+            | Self::DataLoss { meta, .. }
+            | Self::Unauthenticated { meta, .. } => meta,
         }
     }
-}
 
-impl fmt::Display for CanonicalError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.category_name(), self.message())
+    pub fn message(&self) -> &str {
+        &self.meta().message
     }
-}
 
-impl std::error::Error for CanonicalError {}
+    /// Returns the message override for `locale`, if one was set with
+    /// [`CanonicalError::localize`].
+    pub fn localized_message(&self, locale: &str) -> Option<&str> {
+        self.localized_messages().get(locale).map(String::as_str)
+    }
 
-impl GtsSchema for CanonicalError {
-    const SCHEMA_ID: &'static str = "gts.cf.core.errors.canonical_error.v1~";
+    pub fn localized_messages(&self) -> &HashMap<String, String> {
+        &self.meta().localized_messages
+    }
 
-    fn gts_schema_with_refs() -> serde_json::Value {
-        let variant = |name: &str, ctx_ref: &str| {
-            serde_json::json!({
-                "type": "object",
-                "properties": {
-                    "category": { "const": name },
-                    "message": { "type": "string" },
-                    "resource_type": { "type": "string" },
-                    "context": { "$ref": ctx_ref }
-                },
-                "required": ["category", "message", "context"]
-            })
-        };
+    /// Borrows the typed context payload.
+    pub fn context(&self) -> ErrorContext<'_> {
+        match self {
+            Self::Cancelled { ctx, .. } => ErrorContext::RequestInfo(ctx),
+            Self::Unknown { ctx, .. } => ErrorContext::DebugInfo(ctx),
+            Self::InvalidArgument { ctx, .. } => ErrorContext::Validation(ctx),
+            Self::DeadlineExceeded { ctx, .. } => ErrorContext::RequestInfo(ctx),
+            Self::NotFound { ctx, .. } => ErrorContext::ResourceInfo(ctx),
+            Self::AlreadyExists { ctx, .. } => ErrorContext::ResourceInfo(ctx),
+            Self::PermissionDenied { ctx, .. } => ErrorContext::ErrorInfo(ctx),
+            Self::ResourceExhausted { ctx, .. } => ErrorContext::QuotaFailure(ctx),
+            Self::FailedPrecondition { ctx, .. } => ErrorContext::PreconditionFailure(ctx),
+            Self::Aborted { ctx, .. } => ErrorContext::ErrorInfo(ctx),
+            Self::OutOfRange { ctx, .. } => ErrorContext::Validation(ctx),
+            Self::Unimplemented { ctx, .. } => ErrorContext::ErrorInfo(ctx),
+            Self::Internal { ctx, .. } => ErrorContext::DebugInfo(ctx),
+            Self::ServiceUnavailable { ctx, .. } => ErrorContext::RetryInfo(ctx),
+            Self::DataLoss { ctx, .. } => ErrorContext::ResourceInfo(ctx),
+            Self::Unauthenticated { ctx, .. } => ErrorContext::ErrorInfo(ctx),
+        }
+    }
 
-        serde_json::json!({
-            "$id": "gts://gts.cf.core.errors.canonical_error.v1~",
-            "$schema": "http://json-schema.org/draft-07/schema#",
-            "oneOf": [
-                variant("cancelled",          &format!("gts://{}", RequestInfoV1::SCHEMA_ID)),
-                variant("unknown",            &format!("gts://{}", DebugInfoV1::SCHEMA_ID)),
-                variant("invalid_argument",   &format!("gts://{}", Validation::SCHEMA_ID)),
-                variant("deadline_exceeded",   &format!("gts://{}", RequestInfoV1::SCHEMA_ID)),
-                variant("not_found",          &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
-                variant("already_exists",     &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
-                variant("permission_denied",  &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
-                variant("resource_exhausted", &format!("gts://{}", QuotaFailureV1::SCHEMA_ID)),
-                variant("failed_precondition", &format!("gts://{}", PreconditionFailureV1::SCHEMA_ID)),
-                variant("aborted",            &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
-                variant("out_of_range",       &format!("gts://{}", Validation::SCHEMA_ID)),
-                variant("unimplemented",      &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
-                variant("internal",           &format!("gts://{}", DebugInfoV1::SCHEMA_ID)),
-                variant("unavailable",        &format!("gts://{}", RetryInfoV1::SCHEMA_ID)),
-                variant("data_loss",          &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
-                variant("unauthenticated",    &format!("gts://{}", ErrorInfoV1::SCHEMA_ID))
-            ]
-        })
+    // --- Typed variant accessors ---
+
+    pub fn as_cancelled(&self) -> Option<&RequestInfo> {
+        match self {
+            Self::Cancelled { ctx, .. } => Some(ctx),
+            _ => None,
+        }
     }
-}
 
-// ---------------------------------------------------------------------------
-// Problem (RFC 9457)
-// ---------------------------------------------------------------------------
+    pub fn as_unknown(&self) -> Option<&DebugInfo> {
+        match self {
+            Self::Unknown { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Problem {
-    #[serde(rename = "type")]
-    pub problem_type: String,
-    pub title: String,
-    pub status: u16,
-    pub detail: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub instance: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub trace_id: Option<String>,
-    pub context: serde_json::Value,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub debug: Option<serde_json::Value>,
-}
+    pub fn as_invalid_argument(&self) -> Option<&Validation> {
+        match self {
+            Self::InvalidArgument { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-impl Problem {
-    /// Converts a `CanonicalError` into a `Problem` response (production mode).
-    /// Debug info is always omitted.
-    pub fn from_error(err: CanonicalError) -> Self {
-        Self::build(err, false)
+    pub fn as_deadline_exceeded(&self) -> Option<&RequestInfo> {
+        match self {
+            Self::DeadlineExceeded { ctx, .. } => Some(ctx),
+            _ => None,
+        }
     }
 
-    /// Converts a `CanonicalError` into a `Problem` response (debug mode).
-    /// If the error carries `debug_info`, it is included as a top-level `"debug"` key.
-    pub fn from_error_debug(err: CanonicalError) -> Self {
-        Self::build(err, true)
+    pub fn as_not_found(&self) -> Option<&ResourceInfo> {
+        match self {
+            Self::NotFound { ctx, .. } => Some(ctx),
+            _ => None,
+        }
     }
 
-    fn build(err: CanonicalError, include_debug: bool) -> Self {
-        let problem_type = err.gts_type().to_string();
-        let title = err.title().to_string();
-        let status = err.status_code();
-        let detail = err.message().to_string();
-        let mut context = match &err {
-            CanonicalError::Cancelled { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::Unknown { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::InvalidArgument { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::DeadlineExceeded { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::NotFound { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::AlreadyExists { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::PermissionDenied { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::ResourceExhausted { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::FailedPrecondition { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::Aborted { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::OutOfRange { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::Unimplemented { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::Internal { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::ServiceUnavailable { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::DataLoss { ctx, .. } => serde_json::to_value(ctx),
-            CanonicalError::Unauthenticated { ctx, .. } => serde_json::to_value(ctx),
-        }
-        .expect("context serialization should not fail");
-
-        if let Some(rt) = err.resource_type() {
-            context["resource_type"] = serde_json::Value::String(rt.to_string());
+    pub fn as_already_exists(&self) -> Option<&ResourceInfo> {
+        match self {
+            Self::AlreadyExists { ctx, .. } => Some(ctx),
+            _ => None,
         }
+    }
 
-        let debug_value = if include_debug {
-            err.debug_info()
-                .map(|info| serde_json::to_value(info).expect("debug info serialization should not fail"))
-        } else {
-            None
-        };
+    pub fn as_permission_denied(&self) -> Option<&ErrorInfo> {
+        match self {
+            Self::PermissionDenied { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-        Problem {
-            problem_type,
-            title,
-            status,
-            detail,
-            instance: None,
-            trace_id: None,
-            context,
-            debug: debug_value,
+    pub fn as_resource_exhausted(&self) -> Option<&QuotaFailure> {
+        match self {
+            Self::ResourceExhausted { ctx, .. } => Some(ctx),
+            _ => None,
         }
     }
-}
 
-impl From<CanonicalError> for Problem {
-    fn from(err: CanonicalError) -> Self {
-        Problem::from_error(err)
+    pub fn as_failed_precondition(&self) -> Option<&PreconditionFailure> {
+        match self {
+            Self::FailedPrecondition { ctx, .. } => Some(ctx),
+            _ => None,
+        }
     }
-}
 
-// ---------------------------------------------------------------------------
-// Problem → CanonicalError (deserialization / round-trip)
-// ---------------------------------------------------------------------------
+    pub fn as_aborted(&self) -> Option<&ErrorInfo> {
+        match self {
+            Self::Aborted { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-/// Error returned when a `Problem` cannot be converted into a `CanonicalError`.
-#[derive(Debug)]
-pub enum ProblemConversionError {
-    /// The `type` URI does not have the expected GTS prefix format.
-    InvalidType(String),
-    /// The category extracted from the `type` URI is not one of the 16 known categories.
-    UnknownCategory(String),
-    /// The `context` JSON could not be deserialized into the expected struct for this category.
-    ContextDeserializationFailed {
-        category: String,
-        source: serde_json::Error,
-    },
-}
+    pub fn as_out_of_range(&self) -> Option<&Validation> {
+        match self {
+            Self::OutOfRange { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-impl fmt::Display for ProblemConversionError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    pub fn as_unimplemented(&self) -> Option<&ErrorInfo> {
         match self {
-            Self::InvalidType(t) => write!(f, "invalid GTS type URI: {t}"),
-            Self::UnknownCategory(c) => write!(f, "unknown canonical error category: {c}"),
-            Self::ContextDeserializationFailed { category, source } => {
-                write!(f, "failed to deserialize context for {category}: {source}")
-            }
+            Self::Unimplemented { ctx, .. } => Some(ctx),
+            _ => None,
         }
     }
-}
 
-impl std::error::Error for ProblemConversionError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+    pub fn as_internal(&self) -> Option<&DebugInfo> {
         match self {
-            Self::ContextDeserializationFailed { source, .. } => Some(source),
+            Self::Internal { ctx, .. } => Some(ctx),
             _ => None,
         }
     }
-}
 
-/// GTS type URI prefix: `gts.cf.core.errors.err.v1~cf.core.errors.`
-const GTS_TYPE_PREFIX: &str = "gts.cf.core.errors.err.v1~cf.core.errors.";
-/// GTS type URI suffix: `.v1~`
-const GTS_TYPE_SUFFIX: &str = ".v1~";
+    pub fn as_service_unavailable(&self) -> Option<&RetryInfo> {
+        match self {
+            Self::ServiceUnavailable { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-/// Parses a GTS compound type URI and returns the category name.
-///
-/// Example: `"gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~"` → `"not_found"`
-fn parse_category(problem_type: &str) -> Result<&str, ProblemConversionError> {
-    let without_prefix = problem_type
-        .strip_prefix(GTS_TYPE_PREFIX)
-        .ok_or_else(|| ProblemConversionError::InvalidType(problem_type.to_string()))?;
-    let category = without_prefix
-        .strip_suffix(GTS_TYPE_SUFFIX)
-        .ok_or_else(|| ProblemConversionError::InvalidType(problem_type.to_string()))?;
-    Ok(category)
-}
+    pub fn as_data_loss(&self) -> Option<&ResourceInfo> {
+        match self {
+            Self::DataLoss { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-/// Extracts `resource_type` from a context JSON value (if present) as `Option<String>`.
-fn extract_resource_type(context: &serde_json::Value) -> Option<String> {
-    context
-        .get("resource_type")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string())
-}
+    pub fn as_unauthenticated(&self) -> Option<&ErrorInfo> {
+        match self {
+            Self::Unauthenticated { ctx, .. } => Some(ctx),
+            _ => None,
+        }
+    }
 
-/// Deserializes a typed context from a `serde_json::Value`, mapping errors to `ProblemConversionError`.
-fn deser_ctx<T: DeserializeOwned>(
-    context: serde_json::Value,
-    category: &str,
-) -> Result<T, ProblemConversionError> {
-    serde_json::from_value(context).map_err(|source| ProblemConversionError::ContextDeserializationFailed {
-        category: category.to_string(),
-        source,
-    })
-}
+    // --- Context setters ---
+    //
+    // Replace the context payload in place, leaving message, resource type,
+    // debug info and everything else untouched. Each returns `false` (and
+    // drops `ctx`) when the variant carries a different context type.
 
-impl TryFrom<Problem> for CanonicalError {
-    type Error = ProblemConversionError;
+    pub fn set_request_info(&mut self, ctx: RequestInfo) -> bool {
+        match self {
+            Self::Cancelled { ctx: slot, .. }
+            | Self::DeadlineExceeded { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
 
-    fn try_from(problem: Problem) -> Result<Self, Self::Error> {
-        let category = parse_category(&problem.problem_type)?;
-        let resource_type = extract_resource_type(&problem.context);
-        let debug_info: Option<DebugInfo> = problem
-            .debug
-            .map(|v| serde_json::from_value(v))
-            .transpose()
-            .map_err(|source| ProblemConversionError::ContextDeserializationFailed {
-                category: category.to_string(),
-                source,
-            })?;
-        let message = problem.detail;
+    /// Replaces the `Unknown`/`Internal` context, not the separate
+    /// [`with_debug_info`](Self::with_debug_info) entries.
+    pub fn set_debug_context(&mut self, ctx: DebugInfo) -> bool {
+        match self {
+            Self::Unknown { ctx: slot, .. } | Self::Internal { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
 
-        match category {
-            "cancelled" => Ok(CanonicalError::Cancelled {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "unknown" => Ok(CanonicalError::Unknown {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "invalid_argument" => Ok(CanonicalError::InvalidArgument {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "deadline_exceeded" => Ok(CanonicalError::DeadlineExceeded {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "not_found" => Ok(CanonicalError::NotFound {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "already_exists" => Ok(CanonicalError::AlreadyExists {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "permission_denied" => Ok(CanonicalError::PermissionDenied {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "resource_exhausted" => Ok(CanonicalError::ResourceExhausted {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "failed_precondition" => Ok(CanonicalError::FailedPrecondition {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "aborted" => Ok(CanonicalError::Aborted {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "out_of_range" => Ok(CanonicalError::OutOfRange {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "unimplemented" => Ok(CanonicalError::Unimplemented {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "internal" => Ok(CanonicalError::Internal {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "service_unavailable" => Ok(CanonicalError::ServiceUnavailable {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "data_loss" => Ok(CanonicalError::DataLoss {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
-            }),
-            "unauthenticated" => Ok(CanonicalError::Unauthenticated {
-                ctx: deser_ctx(problem.context, category)?,
-                message,
-                resource_type,
-                debug_info,
+    pub fn set_validation(&mut self, ctx: Validation) -> bool {
+        match self {
+            Self::InvalidArgument { ctx: slot, .. }
+            | Self::OutOfRange { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_resource_info(&mut self, ctx: ResourceInfo) -> bool {
+        match self {
+            Self::NotFound { ctx: slot, .. }
+            | Self::AlreadyExists { ctx: slot, .. }
+            | Self::DataLoss { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_error_info(&mut self, ctx: ErrorInfo) -> bool {
+        match self {
+            Self::PermissionDenied { ctx: slot, .. }
+            | Self::Aborted { ctx: slot, .. }
+            | Self::Unimplemented { ctx: slot, .. }
+            | Self::Unauthenticated { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_quota_failure(&mut self, ctx: QuotaFailure) -> bool {
+        match self {
+            Self::ResourceExhausted { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_precondition_failure(&mut self, ctx: PreconditionFailure) -> bool {
+        match self {
+            Self::FailedPrecondition { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn set_retry_info(&mut self, ctx: RetryInfo) -> bool {
+        match self {
+            Self::ServiceUnavailable { ctx: slot, .. } => {
+                *slot = ctx;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // --- Category predicates ---
+
+    pub fn is_cancelled(&self) -> bool {
+        self.category() == Category::Cancelled
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        self.category() == Category::Unknown
+    }
+
+    pub fn is_invalid_argument(&self) -> bool {
+        self.category() == Category::InvalidArgument
+    }
+
+    pub fn is_deadline_exceeded(&self) -> bool {
+        self.category() == Category::DeadlineExceeded
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        self.category() == Category::NotFound
+    }
+
+    pub fn is_already_exists(&self) -> bool {
+        self.category() == Category::AlreadyExists
+    }
+
+    pub fn is_permission_denied(&self) -> bool {
+        self.category() == Category::PermissionDenied
+    }
+
+    pub fn is_resource_exhausted(&self) -> bool {
+        self.category() == Category::ResourceExhausted
+    }
+
+    pub fn is_failed_precondition(&self) -> bool {
+        self.category() == Category::FailedPrecondition
+    }
+
+    pub fn is_aborted(&self) -> bool {
+        self.category() == Category::Aborted
+    }
+
+    pub fn is_out_of_range(&self) -> bool {
+        self.category() == Category::OutOfRange
+    }
+
+    pub fn is_unimplemented(&self) -> bool {
+        self.category() == Category::Unimplemented
+    }
+
+    pub fn is_internal(&self) -> bool {
+        self.category() == Category::Internal
+    }
+
+    pub fn is_service_unavailable(&self) -> bool {
+        self.category() == Category::ServiceUnavailable
+    }
+
+    pub fn is_data_loss(&self) -> bool {
+        self.category() == Category::DataLoss
+    }
+
+    pub fn is_unauthenticated(&self) -> bool {
+        self.category() == Category::Unauthenticated
+    }
+
+    pub fn resource_type(&self) -> Option<&str> {
+        self.meta().resource_type.as_deref()
+    }
+
+    /// For `NotFound`, `AlreadyExists` and `DataLoss`, returns `(tag, ctx)`
+    /// when the error-level `resource_type` differs from
+    /// `ResourceInfo.resource_type`. An unset tag, or an empty context type
+    /// (e.g. from [`CanonicalError::from_status_code`]), is not a mismatch.
+    pub fn resource_type_mismatch(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::NotFound { ctx, meta } | Self::AlreadyExists { ctx, meta } | Self::DataLoss { ctx, meta } => {
+                match meta.resource_type.as_deref() {
+                    Some(rt) if !ctx.resource_type.is_empty() && rt != ctx.resource_type => {
+                        Some((rt, &ctx.resource_type))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of violations carried by `InvalidArgument`/`OutOfRange`
+    /// (see [`Validation::violation_count`]), `ResourceExhausted` or
+    /// `FailedPrecondition`; `None` for categories without a failure list.
+    pub fn violation_count(&self) -> Option<usize> {
+        match self {
+            Self::InvalidArgument { ctx, .. } | Self::OutOfRange { ctx, .. } => Some(ctx.violation_count()),
+            Self::ResourceExhausted { ctx, .. } => Some(ctx.len()),
+            Self::FailedPrecondition { ctx, .. } => Some(ctx.len()),
+            _ => None,
+        }
+    }
+
+    /// Returns the first attached debug info, see [`CanonicalError::debug_infos`].
+    pub fn debug_info(&self) -> Option<&DebugInfo> {
+        self.meta().debug_info.first()
+    }
+
+    /// Returns every attached debug info, in the order it was added.
+    pub fn debug_infos(&self) -> &[DebugInfo] {
+        &self.meta().debug_info
+    }
+
+    /// Returns the relative retry delay for `ServiceUnavailable`; `None` for all
+    /// other categories or when only an absolute `retry_at` is set.
+    pub fn retry_after_seconds(&self) -> Option<u64> {
+        match self {
+            Self::ServiceUnavailable { ctx, .. } => ctx.retry_after_seconds,
+            _ => None,
+        }
+    }
+
+    /// Returns the `ErrorInfo` metadata for the variants that carry one
+    /// (PermissionDenied, Aborted, Unimplemented, Unauthenticated).
+    pub fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        match self {
+            Self::PermissionDenied { ctx, .. }
+            | Self::Aborted { ctx, .. }
+            | Self::Unimplemented { ctx, .. }
+            | Self::Unauthenticated { ctx, .. } => Some(&ctx.metadata),
+            _ => None,
+        }
+    }
+
+    /// Flattens the error into `key=value` pairs for structured logs:
+    /// `error.category`, `error.status`, `error.message`, `error.resource_type`
+    /// (when set) and, for `ErrorInfo` variants, `error.reason`,
+    /// `error.domain` and one `error.meta.<key>` per metadata entry.
+    pub fn to_flat_fields(&self) -> BTreeMap<String, String> {
+        let mut fields = BTreeMap::new();
+        fields.insert("error.category".to_string(), self.category_name().to_string());
+        fields.insert("error.status".to_string(), self.status_code().to_string());
+        fields.insert("error.message".to_string(), self.message().to_string());
+        if let Some(resource_type) = self.resource_type() {
+            fields.insert("error.resource_type".to_string(), resource_type.to_string());
+        }
+        if let ErrorContext::ErrorInfo(info) = self.context() {
+            fields.insert("error.reason".to_string(), info.reason.clone());
+            fields.insert("error.domain".to_string(), info.domain.clone());
+            for (key, value) in &info.metadata {
+                fields.insert(format!("error.meta.{key}"), value.clone());
+            }
+        }
+        fields
+    }
+
+    /// Appends `other`'s field violations to `self`'s. Both must be
+    /// `InvalidArgument` errors carrying `FieldViolations`; otherwise `self`
+    /// is returned unchanged as the `Err`.
+    #[allow(clippy::result_large_err)]
+    pub fn merge_field_violations(mut self, other: CanonicalError) -> Result<CanonicalError, CanonicalError> {
+        let Self::InvalidArgument {
+            ctx: Validation::FieldViolations { field_violations },
+            ..
+        } = &mut self
+        else {
+            return Err(self);
+        };
+        let Self::InvalidArgument {
+            ctx: Validation::FieldViolations { field_violations: more },
+            ..
+        } = other
+        else {
+            return Err(self);
+        };
+        field_violations.extend(more);
+        Ok(self)
+    }
+
+    /// Inserts a metadata entry into the `ErrorInfo` context. Returns `false`
+    /// (and leaves the error untouched) for variants without one.
+    pub fn insert_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) -> bool {
+        match self.metadata_mut() {
+            Some(metadata) => {
+                metadata.insert(key.into(), value.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds `pairs` to the `ErrorInfo` metadata, keeping the existing value
+    /// when a key is already present. A no-op for variants without `ErrorInfo`.
+    pub fn merge_metadata(mut self, pairs: impl IntoIterator<Item = (String, String)>) -> Self {
+        if let Some(metadata) = self.metadata_mut() {
+            for (key, value) in pairs {
+                metadata.entry(key).or_insert(value);
+            }
+        }
+        self
+    }
+
+    fn metadata_mut(&mut self) -> Option<&mut BTreeMap<String, String>> {
+        match self {
+            Self::PermissionDenied { ctx, .. }
+            | Self::Aborted { ctx, .. }
+            | Self::Unimplemented { ctx, .. }
+            | Self::Unauthenticated { ctx, .. } => Some(&mut ctx.metadata),
+            _ => None,
+        }
+    }
+
+    // --- Tracing ---
+
+    /// Emits a structured `tracing` event: `ERROR` for 5xx, `INFO` for
+    /// `Cancelled`, `WARN` for every other category. Carries `category`,
+    /// `status`, `message` and `resource_type`, plus `debug_detail` and
+    /// `stack_entries` when debug info is attached.
+    #[cfg(feature = "tracing")]
+    pub fn emit_tracing_event(&self) {
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    category = self.category_name(),
+                    status = self.status_code(),
+                    message = self.message(),
+                    resource_type = self.resource_type(),
+                    debug_detail = self.debug_info().map(|info| info.detail.as_str()),
+                    stack_entries = self
+                        .debug_info()
+                        .filter(|info| !info.stack_entries.is_empty())
+                        .map(|info| tracing::field::debug(&info.stack_entries)),
+                    "{self}"
+                )
+            };
+        }
+        match self {
+            Self::Cancelled { .. } => emit!(tracing::Level::INFO),
+            _ if self.is_server_error() => emit!(tracing::Level::ERROR),
+            _ => emit!(tracing::Level::WARN),
+        }
+    }
+
+    // --- Fingerprint ---
+
+    /// Stable 64-bit fingerprint for grouping occurrences of the same error.
+    ///
+    /// Hashes (FNV-1a) the category name, the `resource_type` tag, the
+    /// message with every word containing a digit replaced by `#`, and the
+    /// sorted top-level context keys. Context values, debug info and the
+    /// cause are excluded, so request ids, resource names and timestamps do
+    /// not split groups.
+    pub fn fingerprint(&self) -> u64 {
+        let normalized: Vec<&str> = self
+            .message()
+            .split_whitespace()
+            .map(|word| if word.bytes().any(|b| b.is_ascii_digit()) { "#" } else { word })
+            .collect();
+        let context = self.context_value();
+        let mut keys: Vec<&str> = context
+            .as_object()
+            .map(|map| map.keys().map(String::as_str).collect())
+            .unwrap_or_default();
+        // Sorted here so the hash does not depend on serde_json's map order.
+        keys.sort_unstable();
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for part in [self.category_name(), self.resource_type().unwrap_or(""), &normalized.join(" ")] {
+            hash = fnv1a(hash, part.as_bytes());
+            hash = fnv1a(hash, &[0xff]);
+        }
+        for key in keys {
+            hash = fnv1a(hash, key.as_bytes());
+            hash = fnv1a(hash, &[0xff]);
+        }
+        hash
+    }
+
+    // --- Decomposition ---
+
+    /// Consumes the error and returns its typed context payload.
+    pub fn into_context(self) -> OwnedErrorContext {
+        self.into_parts().context
+    }
+
+    /// Consumes the error and returns its owned components.
+    pub fn into_parts(self) -> ErrorParts {
+        let category = self.category();
+        let cancellation_reason = self.cancellation_reason().cloned();
+        let (context, meta, status) = match self {
+            Self::Cancelled { ctx, meta, .. } => (OwnedErrorContext::RequestInfo(ctx), meta, None),
+            Self::Unknown { ctx, meta, status } => (OwnedErrorContext::DebugInfo(ctx), meta, status),
+            Self::InvalidArgument { ctx, meta } => (OwnedErrorContext::Validation(ctx), meta, None),
+            Self::DeadlineExceeded { ctx, meta } => (OwnedErrorContext::RequestInfo(ctx), meta, None),
+            Self::NotFound { ctx, meta } => (OwnedErrorContext::ResourceInfo(ctx), meta, None),
+            Self::AlreadyExists { ctx, meta } => (OwnedErrorContext::ResourceInfo(ctx), meta, None),
+            Self::PermissionDenied { ctx, meta } => (OwnedErrorContext::ErrorInfo(ctx), meta, None),
+            Self::ResourceExhausted { ctx, meta } => (OwnedErrorContext::QuotaFailure(ctx), meta, None),
+            Self::FailedPrecondition { ctx, meta } => (OwnedErrorContext::PreconditionFailure(ctx), meta, None),
+            Self::Aborted { ctx, meta } => (OwnedErrorContext::ErrorInfo(ctx), meta, None),
+            Self::OutOfRange { ctx, meta } => (OwnedErrorContext::Validation(ctx), meta, None),
+            Self::Unimplemented { ctx, meta } => (OwnedErrorContext::ErrorInfo(ctx), meta, None),
+            Self::Internal { ctx, meta } => (OwnedErrorContext::DebugInfo(ctx), meta, None),
+            Self::ServiceUnavailable { ctx, meta } => (OwnedErrorContext::RetryInfo(ctx), meta, None),
+            Self::DataLoss { ctx, meta } => (OwnedErrorContext::ResourceInfo(ctx), meta, None),
+            Self::Unauthenticated { ctx, meta } => (OwnedErrorContext::ErrorInfo(ctx), meta, None),
+        };
+        ErrorParts { category, context, meta, status, cancellation_reason }
+    }
+
+    /// Reassembles an error from [`ErrorParts`]. Fails, returning the parts
+    /// unchanged, when the context type does not belong to the category.
+    #[allow(clippy::result_large_err)]
+    pub fn from_parts(parts: ErrorParts) -> Result<Self, ErrorParts> {
+        let ErrorParts { category, context, meta, status, cancellation_reason } = parts;
+        match (category, context) {
+            (Category::Cancelled, OwnedErrorContext::RequestInfo(ctx)) => {
+                Ok(Self::Cancelled { ctx, meta, reason: cancellation_reason })
+            }
+            (Category::Unknown, OwnedErrorContext::DebugInfo(ctx)) => Ok(Self::Unknown { ctx, meta, status }),
+            (Category::InvalidArgument, OwnedErrorContext::Validation(ctx)) => Ok(Self::InvalidArgument { ctx, meta }),
+            (Category::DeadlineExceeded, OwnedErrorContext::RequestInfo(ctx)) => Ok(Self::DeadlineExceeded { ctx, meta }),
+            (Category::NotFound, OwnedErrorContext::ResourceInfo(ctx)) => Ok(Self::NotFound { ctx, meta }),
+            (Category::AlreadyExists, OwnedErrorContext::ResourceInfo(ctx)) => Ok(Self::AlreadyExists { ctx, meta }),
+            (Category::PermissionDenied, OwnedErrorContext::ErrorInfo(ctx)) => Ok(Self::PermissionDenied { ctx, meta }),
+            (Category::ResourceExhausted, OwnedErrorContext::QuotaFailure(ctx)) => Ok(Self::ResourceExhausted { ctx, meta }),
+            (Category::FailedPrecondition, OwnedErrorContext::PreconditionFailure(ctx)) => Ok(Self::FailedPrecondition { ctx, meta }),
+            (Category::Aborted, OwnedErrorContext::ErrorInfo(ctx)) => Ok(Self::Aborted { ctx, meta }),
+            (Category::OutOfRange, OwnedErrorContext::Validation(ctx)) => Ok(Self::OutOfRange { ctx, meta }),
+            (Category::Unimplemented, OwnedErrorContext::ErrorInfo(ctx)) => Ok(Self::Unimplemented { ctx, meta }),
+            (Category::Internal, OwnedErrorContext::DebugInfo(ctx)) => Ok(Self::Internal { ctx, meta }),
+            (Category::ServiceUnavailable, OwnedErrorContext::RetryInfo(ctx)) => Ok(Self::ServiceUnavailable { ctx, meta }),
+            (Category::DataLoss, OwnedErrorContext::ResourceInfo(ctx)) => Ok(Self::DataLoss { ctx, meta }),
+            (Category::Unauthenticated, OwnedErrorContext::ErrorInfo(ctx)) => Ok(Self::Unauthenticated { ctx, meta }),
+            (category, context) => Err(ErrorParts { category, context, meta, status, cancellation_reason }),
+        }
+    }
+
+    // --- GTS Catalog ---
+
+    pub fn category(&self) -> Category {
+        match self {
+            Self::Cancelled { .. } => Category::Cancelled,
+            Self::Unknown { .. } => Category::Unknown,
+            Self::InvalidArgument { .. } => Category::InvalidArgument,
+            Self::DeadlineExceeded { .. } => Category::DeadlineExceeded,
+            Self::NotFound { .. } => Category::NotFound,
+            Self::AlreadyExists { .. } => Category::AlreadyExists,
+            Self::PermissionDenied { .. } => Category::PermissionDenied,
+            Self::ResourceExhausted { .. } => Category::ResourceExhausted,
+            Self::FailedPrecondition { .. } => Category::FailedPrecondition,
+            Self::Aborted { .. } => Category::Aborted,
+            Self::OutOfRange { .. } => Category::OutOfRange,
+            Self::Unimplemented { .. } => Category::Unimplemented,
+            Self::Internal { .. } => Category::Internal,
+            Self::ServiceUnavailable { .. } => Category::ServiceUnavailable,
+            Self::DataLoss { .. } => Category::DataLoss,
+            Self::Unauthenticated { .. } => Category::Unauthenticated,
+        }
+    }
+
+    pub fn gts_type(&self) -> &'static str {
+        self.category().gts_type()
+    }
+
+    /// Returns the reason recorded by [`CanonicalError::cancelled_with_reason`];
+    /// `None` for all other errors.
+    pub fn cancellation_reason(&self) -> Option<&CancellationReason> {
+        match self {
+            Self::Cancelled { reason, .. } => reason.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the HTTP status code: the category default, or the upstream
+    /// status preserved by [`CanonicalError::unknown_with_status`].
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Unknown { status: Some(status), .. } => *status,
+            _ => self.category().status_code(),
+        }
+    }
+
+    /// Returns the HTTP reason phrase for `status_code()`, e.g. `"Not Found"`;
+    /// `None` for a status this crate has no phrase for (e.g. an `Unknown`
+    /// override of 418). 499 uses the nginx convention `"Client Closed Request"`.
+    pub fn reason_phrase(&self) -> Option<&'static str> {
+        http_reason_phrase(self.status_code())
+    }
+
+    /// Returns an HTTP/1.1 status line fragment, e.g. `"404 Not Found"`, or
+    /// just the code when there is no reason phrase.
+    pub fn status_line(&self) -> String {
+        match self.reason_phrase() {
+            Some(phrase) => format!("{} {phrase}", self.status_code()),
+            None => self.status_code().to_string(),
+        }
+    }
+
+    /// Returns the canonical gRPC status code, see [`Category::grpc_code`].
+    pub fn grpc_code(&self) -> i32 {
+        self.category().grpc_code()
+    }
+
+    /// Returns the `grpc-status` and `grpc-message` trailers for a grpc-web
+    /// response. The message is percent-encoded as the gRPC HTTP/2 spec
+    /// requires: bytes outside printable ASCII, and `%` itself, become `%XX`.
+    pub fn grpc_trailers(&self) -> Vec<(String, String)> {
+        vec![
+            ("grpc-status".to_string(), self.grpc_code().to_string()),
+            ("grpc-message".to_string(), grpc_percent_encode(self.message())),
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.category().title()
+    }
+
+    /// True when [`status_code`](Self::status_code) is 5xx: `Unknown`,
+    /// `DeadlineExceeded`, `Unimplemented`, `Internal`, `ServiceUnavailable`
+    /// and `DataLoss`. An `Unknown` with a status override follows the override.
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.status_code())
+    }
+
+    /// True when [`status_code`](Self::status_code) is 4xx: `Cancelled` (499),
+    /// `InvalidArgument`, `NotFound`, `AlreadyExists`, `PermissionDenied`,
+    /// `ResourceExhausted`, `FailedPrecondition`, `Aborted`, `OutOfRange` and
+    /// `Unauthenticated`.
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.status_code())
+    }
+
+    /// True for transient categories where retrying the same request may
+    /// succeed: `ServiceUnavailable`, `DeadlineExceeded`, `Aborted` and
+    /// `ResourceExhausted`.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::ServiceUnavailable { .. }
+                | Self::DeadlineExceeded { .. }
+                | Self::Aborted { .. }
+                | Self::ResourceExhausted { .. }
+        )
+    }
+
+    /// Log line built only from static, category-level data:
+    /// `category: default message (status=…, title=…, resource_type=…)`.
+    ///
+    /// Nothing from `DebugInfo` (detail or stack entries), the context or
+    /// the actual message appears, so values that may carry SQL, secrets or
+    /// user input cannot leak. `resource_type` is included as set by the
+    /// service.
+    pub fn to_safe_log_string(&self) -> String {
+        let category = self.category();
+        let mut out = format!(
+            "{}: {} (status={}, title={}",
+            category.name(),
+            category.default_message(),
+            self.status_code(),
+            category.title()
+        );
+        if let Some(resource_type) = self.resource_type() {
+            out.push_str(&format!(", resource_type={resource_type}"));
+        }
+        out.push(')');
+        out
+    }
+
+    /// Returns the `Problem` status, title and type without building the body.
+    /// The status honors the `Unknown` override, as [`Problem::from_error`] does.
+    pub fn problem_head(&self) -> ProblemHead {
+        ProblemHead {
+            status: self.status_code(),
+            title: self.title(),
+            problem_type: self.gts_type(),
+        }
+    }
+
+    /// One-line summary for terminals: status, title, message, resource type
+    /// and the most salient context field, e.g.
+    /// `400 Invalid Argument: Request validation failed [gts.cf.core.users.user.v1] (email: required)`.
+    ///
+    /// `Display` keeps the compact `category: message` form for logs.
+    pub fn to_human_string(&self) -> String {
+        let mut out = format!("{} {}: {}", self.status_code(), self.title(), self.message());
+        if let Some(resource_type) = self.resource_type() {
+            out.push_str(&format!(" [{resource_type}]"));
+        }
+        if let Some(salient) = self.salient_context() {
+            out.push_str(&format!(" ({salient})"));
+        }
+        out
+    }
+
+    fn salient_context(&self) -> Option<String> {
+        match self.context() {
+            ErrorContext::RequestInfo(info) => Some(match info.elapsed_ms {
+                Some(ms) => format!("request_id: {}, elapsed: {ms}ms", info.request_id),
+                None => format!("request_id: {}", info.request_id),
             }),
-            _ => Err(ProblemConversionError::UnknownCategory(category.to_string())),
+            ErrorContext::DebugInfo(_) => None,
+            ErrorContext::Validation(Validation::FieldViolations { field_violations }) => field_violations
+                .first()
+                .map(|v| format!("{}: {}", v.field, v.description)),
+            ErrorContext::Validation(Validation::Format { format }) => Some(format.clone()),
+            ErrorContext::Validation(Validation::Constraint { constraint }) => Some(constraint.clone()),
+            ErrorContext::ResourceInfo(info) => Some(format!("resource_name: {}", info.resource_name)),
+            ErrorContext::ErrorInfo(info) => Some(format!("reason: {}", info.reason)),
+            ErrorContext::QuotaFailure(failure) => failure
+                .violations
+                .first()
+                .map(|v| format!("{}: {}", v.subject, v.description)),
+            ErrorContext::PreconditionFailure(failure) => failure
+                .violations
+                .first()
+                .map(|v| format!("{} {}: {}", v.precondition_type, v.subject, v.description)),
+            ErrorContext::RetryInfo(info) => match (info.retry_after_seconds, &info.retry_at) {
+                (Some(seconds), _) => Some(format!("retry after {seconds}s")),
+                (None, Some(at)) => Some(format!("retry at {at}")),
+                (None, None) => None,
+            },
+        }
+    }
+
+    /// Wraps the error in an `anyhow::Error`; converting it back with `From`
+    /// returns the original error rather than an `Internal` wrapper.
+    #[cfg(feature = "anyhow")]
+    pub fn context_anyhow(self) -> anyhow::Error {
+        anyhow::Error::new(self)
+    }
+
+    /// Triage rank, higher meaning more severe:
+    ///
+    /// - 3: `Internal`, `DataLoss`
+    /// - 2: any other error with a 5xx status
+    /// - 1: everything else (4xx, or a non-5xx `Unknown` override)
+    /// - 0: `Cancelled`
+    pub fn severity_rank(&self) -> u8 {
+        match self {
+            Self::Internal { .. } | Self::DataLoss { .. } => 3,
+            Self::Cancelled { .. } => 0,
+            _ if self.status_code() >= 500 => 2,
+            _ => 1,
+        }
+    }
+
+    fn category_name(&self) -> &'static str {
+        self.category().name()
+    }
+
+    /// Returns the context exactly as [`Problem::from_error`] emits it in
+    /// `context`, including the injected `resource_type`, without building
+    /// the rest of the problem.
+    pub fn context_json(&self) -> serde_json::Value {
+        self.try_context_json().expect("context serialization should not fail")
+    }
+
+    fn try_context_json(&self) -> Result<serde_json::Value, serde_json::Error> {
+        let mut context = self.try_context_value()?;
+        if let Some(rt) = self.resource_type() {
+            context["resource_type"] = serde_json::Value::String(rt.to_string());
+        }
+        Ok(context)
+    }
+
+    /// Serializes the typed context payload (without the injected `resource_type`).
+    fn context_value(&self) -> serde_json::Value {
+        self.try_context_value().expect("context serialization should not fail")
+    }
+
+    fn try_context_value(&self) -> Result<serde_json::Value, serde_json::Error> {
+        match self {
+            Self::Cancelled { ctx, .. } => serde_json::to_value(ctx),
+            Self::Unknown { ctx, .. } => serde_json::to_value(ctx),
+            Self::InvalidArgument { ctx, .. } => serde_json::to_value(ctx),
+            Self::DeadlineExceeded { ctx, .. } => serde_json::to_value(ctx),
+            Self::NotFound { ctx, .. } => serde_json::to_value(ctx),
+            Self::AlreadyExists { ctx, .. } => serde_json::to_value(ctx),
+            Self::PermissionDenied { ctx, .. } => serde_json::to_value(ctx),
+            Self::ResourceExhausted { ctx, .. } => serde_json::to_value(ctx),
+            Self::FailedPrecondition { ctx, .. } => serde_json::to_value(ctx),
+            Self::Aborted { ctx, .. } => serde_json::to_value(ctx),
+            Self::OutOfRange { ctx, .. } => serde_json::to_value(ctx),
+            Self::Unimplemented { ctx, .. } => serde_json::to_value(ctx),
+            Self::Internal { ctx, .. } => serde_json::to_value(ctx),
+            Self::ServiceUnavailable { ctx, .. } => serde_json::to_value(ctx),
+            Self::DataLoss { ctx, .. } => serde_json::to_value(ctx),
+            Self::Unauthenticated { ctx, .. } => serde_json::to_value(ctx),
+        }
+    }
+}
+
+/// Picks the error with the highest [`CanonicalError::severity_rank`],
+/// preferring the earliest one on ties.
+pub fn most_severe(errors: &[CanonicalError]) -> Option<&CanonicalError> {
+    errors.iter().rev().max_by_key(|err| err.severity_rank())
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+    hash
+}
+
+fn grpc_percent_encode(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    for &byte in message.as_bytes() {
+        if (0x20..=0x7e).contains(&byte) && byte != b'%' {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    out
+}
+
+fn http_reason_phrase(status: u16) -> Option<&'static str> {
+    let phrase = match status {
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        412 => "Precondition Failed",
+        416 => "Range Not Satisfiable",
+        429 => "Too Many Requests",
+        499 => "Client Closed Request",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => return None,
+    };
+    Some(phrase)
+}
+
+#[cfg(feature = "http")]
+impl From<Category> for http::StatusCode {
+    fn from(category: Category) -> Self {
+        http::StatusCode::from_u16(category.status_code())
+            .expect("canonical status codes are valid HTTP status codes")
+    }
+}
+
+/// Uses the `Unknown` status override when it is a valid HTTP status, and
+/// the category default otherwise.
+#[cfg(feature = "http")]
+impl From<&CanonicalError> for http::StatusCode {
+    fn from(err: &CanonicalError) -> Self {
+        http::StatusCode::from_u16(err.status_code()).unwrap_or_else(|_| err.category().into())
+    }
+}
+
+#[cfg(feature = "http")]
+impl CanonicalError {
+    /// Shorthand for `Problem::from_error(self).into_http_response()`.
+    pub fn into_http_response<B: From<String>>(self) -> http::Response<B> {
+        Problem::from_error(self).into_http_response()
+    }
+}
+
+/// Renders `category: message`. The alternate form (`{:#}`) appends the
+/// context as indented JSON on the following lines.
+impl fmt::Display for CanonicalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.category_name(), self.message())?;
+        if f.alternate() {
+            let context = serde_json::to_string_pretty(&self.context_value()).map_err(|_| fmt::Error)?;
+            write!(f, "\n{context}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Maps a JSON parse failure to `InvalidArgument`:
+///
+/// - a data error naming a field (`missing field` / `unknown field`)
+///   becomes a `FieldViolation` on that field, with reason `REQUIRED` for
+///   missing fields and `INVALID_FORMAT` otherwise;
+/// - every other error (syntax, EOF, I/O, unnamed data errors) becomes
+///   `Validation::format` with the parser's message.
+///
+/// The parse position is recorded as a `DebugInfo` (`"line L, column C"`)
+/// and the original error is kept as the [`std::error::Error::source`].
+impl From<serde_json::Error> for CanonicalError {
+    fn from(err: serde_json::Error) -> Self {
+        let message = err.to_string();
+        let field = match err.classify() {
+            serde_json::error::Category::Data => message
+                .strip_prefix("missing field `")
+                .map(|rest| (rest, FieldViolationReason::Required))
+                .or_else(|| {
+                    message
+                        .strip_prefix("unknown field `")
+                        .map(|rest| (rest, FieldViolationReason::InvalidFormat))
+                })
+                .and_then(|(rest, reason)| rest.split_once('`').map(|(name, _)| (name.to_string(), reason))),
+            _ => None,
+        };
+        let validation = match field {
+            Some((name, reason)) => Validation::fields(vec![FieldViolation::new(name, message.clone(), reason)]),
+            None => Validation::format(message),
+        };
+        let position = DebugInfo::new(format!("line {}, column {}", err.line(), err.column()));
+        CanonicalError::invalid_argument(validation)
+            .with_debug_info(position)
+            .with_cause(Box::new(err))
+    }
+}
+
+/// Unwraps an `anyhow::Error` that already wraps a `CanonicalError`;
+/// anything else becomes `Internal` with the error's `Display` text as the
+/// detail, each cause in the chain as a `"caused by: ..."` stack entry,
+/// followed by the backtrace lines when one was captured.
+#[cfg(feature = "anyhow")]
+impl From<anyhow::Error> for CanonicalError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<CanonicalError>() {
+            Ok(canonical) => return canonical,
+            Err(err) => err,
+        };
+        let mut entries: Vec<String> = err.chain().skip(1).map(|cause| format!("caused by: {cause}")).collect();
+        let backtrace = err.backtrace();
+        if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+            entries.extend(
+                backtrace
+                    .to_string()
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(String::from),
+            );
         }
+        let info = DebugInfo::new(err.to_string()).with_stack(entries);
+        CanonicalError::internal(info).with_cause(err)
+    }
+}
+
+/// Maps the common data-layer failures:
+///
+/// - `RowNotFound` → `NotFound` with an empty `ResourceInfo`
+/// - `PoolTimedOut` → `DeadlineExceeded`
+/// - a database error with SQLSTATE `23505` (unique violation) →
+///   `AlreadyExists` with an empty `ResourceInfo` and the default message;
+///   the driver's text (which names constraints, tables and columns) goes
+///   into a redactable `DebugInfo` only
+/// - anything else → `Internal`, with the error text as `DebugInfo.detail`
+///
+/// The driver cannot know which resource was involved, so callers should
+/// follow up with [`CanonicalError::with_resource_type`] where they can.
+#[cfg(feature = "sqlx")]
+impl From<sqlx::Error> for CanonicalError {
+    fn from(err: sqlx::Error) -> Self {
+        let mapped = match &err {
+            sqlx::Error::RowNotFound => CanonicalError::not_found(ResourceInfo::new("", "")),
+            sqlx::Error::PoolTimedOut => CanonicalError::deadline_exceeded(RequestInfo::new("")),
+            sqlx::Error::Database(db_err) if db_err.code().as_deref() == Some("23505") => {
+                let ctx = ResourceInfo::new("", "").with_description(Category::AlreadyExists.default_message());
+                CanonicalError::already_exists(ctx).add_debug_info(DebugInfo::new(db_err.message()))
+            }
+            _ => CanonicalError::internal(DebugInfo::new(err.to_string())),
+        };
+        mapped.with_cause(err)
+    }
+}
+
+/// Flattens `validator` errors into `InvalidArgument` field violations,
+/// sorted by field path. Nested structs use dotted paths (`address.city`)
+/// and list items an index (`items[0].name`). Validator codes map to
+/// reasons: `length`/`range` → `OUT_OF_RANGE`, `required` → `REQUIRED`,
+/// `must_match` → `CONSTRAINT_VIOLATION`, format checks (`email`, `url`,
+/// `regex`, ...) → `INVALID_FORMAT`; other codes are kept uppercased.
+#[cfg(feature = "validator")]
+impl From<validator::ValidationErrors> for CanonicalError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        fn flatten(prefix: &str, errors: &validator::ValidationErrors, out: &mut Vec<FieldViolation>) {
+            for (field, kind) in errors.errors() {
+                let path = if prefix.is_empty() { field.to_string() } else { format!("{prefix}.{field}") };
+                match kind {
+                    validator::ValidationErrorsKind::Field(field_errors) => {
+                        for err in field_errors {
+                            let description = match &err.message {
+                                Some(message) => message.to_string(),
+                                None => format!("failed `{}` validation", err.code),
+                            };
+                            out.push(FieldViolation::new(path.clone(), description, reason(&err.code)));
+                        }
+                    }
+                    validator::ValidationErrorsKind::Struct(nested) => flatten(&path, nested, out),
+                    validator::ValidationErrorsKind::List(items) => {
+                        for (index, nested) in items {
+                            flatten(&format!("{path}[{index}]"), nested, out);
+                        }
+                    }
+                }
+            }
+        }
+
+        fn reason(code: &str) -> FieldViolationReason {
+            match code {
+                "length" | "range" => FieldViolationReason::OutOfRange,
+                "required" => FieldViolationReason::Required,
+                "must_match" => FieldViolationReason::ConstraintViolation,
+                "email" | "url" | "regex" | "contains" | "does_not_contain" | "credit_card"
+                | "non_control_character" | "ip" | "ipv4" | "ipv6" => FieldViolationReason::InvalidFormat,
+                other => FieldViolationReason::from(other.to_uppercase()),
+            }
+        }
+
+        let mut violations = Vec::new();
+        flatten("", &errors, &mut violations);
+        violations.sort_by(|a, b| a.field.cmp(&b.field));
+        CanonicalError::invalid_argument(Validation::fields(violations))
+    }
+}
+
+/// Funnels an untyped error into `Internal`, built with
+/// [`DebugInfo::from_error_chain`]: the error's `Display` text becomes the
+/// detail and each `source()` cause a `"caused by: ..."` stack entry. The
+/// error itself is kept as the [`std::error::Error::source`].
+impl From<Box<dyn std::error::Error + Send + Sync>> for CanonicalError {
+    fn from(err: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        CanonicalError::internal(DebugInfo::from_error_chain(&*err)).with_cause(err)
+    }
+}
+
+/// Maps a failed integer parse (typically a query parameter) to
+/// `InvalidArgument` with `Validation::format`. The error does not know
+/// which field was being parsed; use [`CanonicalError::invalid_field`] when
+/// the caller does.
+impl From<std::num::ParseIntError> for CanonicalError {
+    fn from(err: std::num::ParseIntError) -> Self {
+        CanonicalError::invalid_argument(Validation::format(err.to_string())).with_cause(Box::new(err))
+    }
+}
+
+/// Float counterpart of the `ParseIntError` conversion.
+impl From<std::num::ParseFloatError> for CanonicalError {
+    fn from(err: std::num::ParseFloatError) -> Self {
+        CanonicalError::invalid_argument(Validation::format(err.to_string())).with_cause(Box::new(err))
+    }
+}
+
+/// Lifts any axum rejection into a canonical error through its HTTP status
+/// (see [`CanonicalError::from_status_code`]), using its `Display` text as
+/// the message.
+#[cfg(feature = "axum")]
+pub fn reject_as<T: axum::response::IntoResponse + fmt::Display>(rejection: T) -> CanonicalError {
+    let detail = rejection.to_string();
+    let status = rejection.into_response().status();
+    CanonicalError::from_status_code(status.as_u16(), detail)
+}
+
+/// Maps a `Json<T>` extractor rejection to `InvalidArgument`:
+///
+/// - a body that fails to parse or deserialize becomes `Validation::format`
+///   with axum's message;
+/// - a missing `Content-Type: application/json` header becomes
+///   `Validation::constraint`;
+/// - anything else (e.g. failing to buffer the body) goes through
+///   [`reject_as`].
+#[cfg(feature = "axum")]
+impl From<axum::extract::rejection::JsonRejection> for CanonicalError {
+    fn from(rejection: axum::extract::rejection::JsonRejection) -> Self {
+        use axum::extract::rejection::JsonRejection;
+
+        match rejection {
+            JsonRejection::JsonDataError(_) | JsonRejection::JsonSyntaxError(_) => {
+                CanonicalError::invalid_argument(Validation::format(rejection.body_text()))
+            }
+            JsonRejection::MissingJsonContentType(_) => {
+                CanonicalError::invalid_argument(Validation::constraint(rejection.body_text()))
+            }
+            other => reject_as(other),
+        }
+    }
+}
+
+impl std::error::Error for CanonicalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.meta().source.as_ref().map(|s| s.get() as _)
+    }
+}
+
+/// Hashes every field the derived `PartialEq` compares, so errors that are
+/// equal always hash equally: category, context, message, resource type,
+/// debug info, the source (by its `Display` text, as `ErrorSource`
+/// compares it), instance, trace id, code, status, and the localized
+/// messages as a sorted snapshot since `HashMap` is not `Hash`.
+#[cfg(feature = "hash")]
+impl Hash for CanonicalError {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.category().hash(state);
+        self.context().hash(state);
+        self.message().hash(state);
+        self.resource_type().hash(state);
+        self.debug_infos().hash(state);
+        std::error::Error::source(self).map(ToString::to_string).hash(state);
+        (self.instance(), self.trace_id(), self.code()).hash(state);
+        self.status_code().hash(state);
+        let mut localized: Vec<_> = self.localized_messages().iter().collect();
+        localized.sort();
+        localized.hash(state);
+    }
+}
+
+impl GtsSchema for CanonicalError {
+    const SCHEMA_ID: &'static str = "gts.cf.core.errors.canonical_error.v1~";
+
+    fn gts_schema_with_refs() -> serde_json::Value {
+        let variant = |name: &str, ctx_ref: &str| {
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "category": { "const": name },
+                    "message": { "type": "string" },
+                    "resource_type": { "type": "string" },
+                    "context": { "$ref": ctx_ref }
+                },
+                "required": ["category", "message", "context"]
+            })
+        };
+
+        serde_json::json!({
+            "$id": "gts://gts.cf.core.errors.canonical_error.v1~",
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "oneOf": [
+                variant("cancelled",          &format!("gts://{}", RequestInfoV1::SCHEMA_ID)),
+                variant("unknown",            &format!("gts://{}", DebugInfoV1::SCHEMA_ID)),
+                variant("invalid_argument",   &format!("gts://{}", Validation::SCHEMA_ID)),
+                variant("deadline_exceeded",   &format!("gts://{}", RequestInfoV1::SCHEMA_ID)),
+                variant("not_found",          &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
+                variant("already_exists",     &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
+                variant("permission_denied",  &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
+                variant("resource_exhausted", &format!("gts://{}", QuotaFailureV1::SCHEMA_ID)),
+                variant("failed_precondition", &format!("gts://{}", PreconditionFailureV1::SCHEMA_ID)),
+                variant("aborted",            &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
+                variant("out_of_range",       &format!("gts://{}", Validation::SCHEMA_ID)),
+                variant("unimplemented",      &format!("gts://{}", ErrorInfoV1::SCHEMA_ID)),
+                variant("internal",           &format!("gts://{}", DebugInfoV1::SCHEMA_ID)),
+                variant("service_unavailable", &format!("gts://{}", RetryInfoV1::SCHEMA_ID)),
+                variant("data_loss",          &format!("gts://{}", ResourceInfoV1::SCHEMA_ID)),
+                variant("unauthenticated",    &format!("gts://{}", ErrorInfoV1::SCHEMA_ID))
+            ]
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Problem (RFC 9457)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Problem {
+    #[serde(rename = "type")]
+    pub problem_type: Cow<'static, str>,
+    pub title: Cow<'static, str>,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// Machine-readable category name, so consumers need not parse `type`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Short symbolic code set with [`CanonicalError::with_code`]. Kept apart
+    /// from `code`, which always holds the category name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    pub context: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub debug: Option<serde_json::Value>,
+    /// RFC 9457 extension members, flattened into the top-level object.
+    /// Unknown top-level keys land here on deserialization.
+    #[serde(flatten)]
+    pub extensions: HashMap<String, serde_json::Value>,
+}
+
+/// Media type for RFC 9457 problem details serialized as JSON.
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Media type for RFC 9457 problem details serialized as XML.
+#[cfg(feature = "xml")]
+pub const PROBLEM_XML_CONTENT_TYPE: &str = "application/problem+xml";
+
+/// Top-level `Problem` members that cannot be used as extension keys.
+const RESERVED_PROBLEM_KEYS: &[&str] = &[
+    "type", "title", "status", "detail", "instance", "trace_id", "code", "error_code", "context", "debug",
+];
+
+impl Problem {
+    /// Converts a `CanonicalError` into a `Problem` response (production mode).
+    /// Debug info is always omitted.
+    pub fn from_error(err: CanonicalError) -> Self {
+        Self::try_from_error(err).expect("problem serialization should not fail")
+    }
+
+    /// Like [`Problem::from_error`], but returns context serialization
+    /// failures instead of panicking.
+    pub fn try_from_error(err: CanonicalError) -> Result<Self, serde_json::Error> {
+        Self::try_build(&err, false)
+    }
+
+    /// Like [`Problem::from_error`], but borrows the error so it can still be
+    /// logged afterwards; only the serialized parts are copied.
+    pub fn from_error_ref(err: &CanonicalError) -> Self {
+        Self::build(err, false)
+    }
+
+    /// Converts a `CanonicalError` into a `Problem` response (debug mode).
+    /// If the error carries `debug_info`, it is included as a top-level `"debug"` key:
+    /// an object for a single entry, an array when several were added.
+    pub fn from_error_debug(err: CanonicalError) -> Self {
+        Self::build(&err, true)
+    }
+
+    /// Like [`Problem::from_error_debug`], but passes `DebugInfo.detail` and
+    /// every stack entry through `redactor` before embedding them.
+    pub fn from_error_debug_with(mut err: CanonicalError, redactor: &(impl DebugRedactor + ?Sized)) -> Self {
+        for info in &mut err.meta_mut().debug_info {
+            info.detail = redactor.redact(&info.detail);
+            for entry in &mut info.stack_entries {
+                *entry = redactor.redact(entry);
+            }
+        }
+        Self::build(&err, true)
+    }
+
+    /// Like [`Problem::from_error`], but takes the HTTP status from `policy`.
+    pub fn from_error_with_policy(err: CanonicalError, policy: &StatusCodePolicy) -> Self {
+        let status = policy.status_code(&err);
+        let mut problem = Self::build(&err, false);
+        problem.status = status;
+        problem
+    }
+
+    /// Like [`Problem::from_error`], with `instance` set to `ctx.path`
+    /// verbatim and `trace_id` to `ctx.trace_id`. Empty values are left unset.
+    pub fn from_error_with_context(err: CanonicalError, ctx: &ProblemContext) -> Self {
+        let mut problem = Self::build(&err, false);
+        if !ctx.path.is_empty() {
+            problem.instance = Some(ctx.path.clone());
+        }
+        if let Some(trace_id) = ctx.trace_id.as_deref().filter(|id| !id.is_empty()) {
+            problem.trace_id = Some(trace_id.to_string());
+        }
+        problem
+    }
+
+    pub fn content_type() -> &'static str {
+        PROBLEM_JSON_CONTENT_TYPE
+    }
+
+    /// Returns `(status, content type, JSON body)` for framework-agnostic
+    /// response construction.
+    pub fn to_response_parts(&self) -> (u16, &'static str, String) {
+        let body = serde_json::to_string(self).expect("problem serialization should not fail");
+        (self.status, PROBLEM_JSON_CONTENT_TYPE, body)
+    }
+
+    /// Builds an `http::Response` with the problem's status, the
+    /// [`response_headers`](Self::response_headers) and the JSON body. A
+    /// status outside 100..=999 becomes 500; header values that are not
+    /// valid in HTTP (e.g. a `trace_id` with control characters) are dropped.
+    #[cfg(feature = "http")]
+    pub fn into_http_response<B: From<String>>(self) -> http::Response<B> {
+        let headers = self.response_headers();
+        let (status, _, body) = self.to_response_parts();
+        let mut response = http::Response::new(B::from(body));
+        *response.status_mut() =
+            http::StatusCode::from_u16(status).unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        for (name, value) in headers {
+            if let Ok(value) = http::HeaderValue::from_str(&value) {
+                let name = http::HeaderName::from_bytes(name.as_bytes()).expect("header names are valid");
+                response.headers_mut().insert(name, value);
+            }
+        }
+        response
+    }
+
+    /// Renders the RFC 9457 XML form (`<problem xmlns="urn:ietf:rfc:7807">`).
+    /// Members appear in the JSON field order, extensions sorted by key.
+    /// Objects become nested elements and arrays a sequence of `<i>` items,
+    /// as in the RFC's appendix; `null` becomes an empty element. Text is
+    /// XML-escaped, with characters XML 1.0 forbids (e.g. U+0000) dropped.
+    /// Keys come from user data such as `ErrorInfo.metadata`, so a key that
+    /// is not a valid XML name (e.g. `a><b`) is written as
+    /// `<i name="a&gt;&lt;b">` instead.
+    #[cfg(feature = "xml")]
+    pub fn to_problem_xml(&self) -> String {
+        let mut out = String::from(r#"<?xml version="1.0" encoding="UTF-8"?><problem xmlns="urn:ietf:rfc:7807">"#);
+        let value = serde_json::to_value(self).expect("problem serialization should not fail");
+        let mut members = value.as_object().cloned().unwrap_or_default();
+        for key in RESERVED_PROBLEM_KEYS {
+            if let Some(member) = members.remove(*key) {
+                write_xml_element(&mut out, key, &member);
+            }
+        }
+        for (key, member) in &members {
+            write_xml_element(&mut out, key, member);
+        }
+        out.push_str("</problem>");
+        out
+    }
+
+    /// Checks that `type` (or the `gts_type` extension left by
+    /// [`with_type_url`](Self::with_type_url)) names a known category, that
+    /// `code`, when present, names the same one, and that `status` is that
+    /// category's canonical status code. `unknown` accepts any status, since
+    /// it may carry an upstream override. Problems built with a
+    /// [`StatusCodePolicy`] override will not validate.
+    pub fn validate(&self) -> Result<(), ProblemConversionError> {
+        let category = problem_type_category(self)?;
+        if let Some(code) = self.code.as_deref()
+            && category_by_name(code)? != category
+        {
+            return Err(ProblemConversionError::CategoryMismatch {
+                code: code.to_string(),
+                category: category.name().to_string(),
+            });
+        }
+        let expected = category.status_code();
+        if category != Category::Unknown && self.status != expected {
+            return Err(ProblemConversionError::StatusMismatch {
+                category: category.name().to_string(),
+                expected,
+                actual: self.status,
+            });
+        }
+        Ok(())
+    }
+
+    /// Sets the RFC 9457 `instance` member (typically the request path).
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// Sets the `trace_id` member (typically from the active tracing context).
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+
+    /// Adds an RFC 9457 extension member. Keys that collide with the reserved
+    /// `Problem` members (`type`, `title`, `status`, ...) are ignored.
+    pub fn with_extension(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<serde_json::Value>,
+    ) -> Self {
+        let key = key.into();
+        if !RESERVED_PROBLEM_KEYS.contains(&key.as_str()) {
+            self.extensions.insert(key, value.into());
+        }
+        self
+    }
+
+    /// Replaces the GTS URN in `type` with a dereferenceable URL,
+    /// `{base}/{category}` (e.g. `https://errors.example.com/not_found`), and
+    /// keeps the URN under the `gts_type` extension member. Leaves the
+    /// problem unchanged when `type` is not a canonical GTS URN.
+    pub fn with_type_url(mut self, base: &str) -> Self {
+        let Ok(category) = parse_category(&self.problem_type) else {
+            return self;
+        };
+        let url = format!("{}/{category}", base.trim_end_matches('/'));
+        let urn = std::mem::replace(&mut self.problem_type, url.into());
+        self.extensions.insert("gts_type".to_string(), serde_json::Value::String(urn.into_owned()));
+        self
+    }
+
+    /// Formats `context.retry_after_seconds` as a `Retry-After` delay-seconds value.
+    pub fn retry_after_header(&self) -> Option<String> {
+        self.context
+            .get("retry_after_seconds")
+            .and_then(serde_json::Value::as_u64)
+            .map(|seconds| seconds.to_string())
+    }
+
+    /// Returns the headers a problem response should carry, in this order:
+    /// `Content-Type` (always [`PROBLEM_JSON_CONTENT_TYPE`]), `Retry-After`
+    /// (see [`retry_after_header`](Self::retry_after_header)) and `X-Trace-Id`
+    /// when `trace_id` is set. Each header appears at most once.
+    pub fn response_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("Content-Type", PROBLEM_JSON_CONTENT_TYPE.to_string())];
+        if let Some(retry_after) = self.retry_after_header() {
+            headers.push(("Retry-After", retry_after));
+        }
+        if let Some(trace_id) = &self.trace_id {
+            headers.push(("X-Trace-Id", trace_id.clone()));
+        }
+        headers
+    }
+
+    /// Reads newline-delimited JSON, yielding one `Problem` per non-blank line.
+    /// I/O failures are surfaced as `serde_json::Error` of category `Io`.
+    pub fn from_ndjson_reader<R: io::Read>(
+        reader: R,
+    ) -> impl Iterator<Item = Result<Problem, serde_json::Error>> {
+        io::BufReader::new(reader)
+            .lines()
+            .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+            .map(|line| {
+                let line = line.map_err(serde_json::Error::io)?;
+                serde_json::from_str(&line)
+            })
+    }
+
+    fn build(err: &CanonicalError, include_debug: bool) -> Self {
+        Self::try_build(err, include_debug).expect("problem serialization should not fail")
+    }
+
+    fn try_build(err: &CanonicalError, include_debug: bool) -> Result<Self, serde_json::Error> {
+        let head = err.problem_head();
+        let detail = err.message().to_string();
+        let code = Some(err.category_name().to_string());
+        let context = err.try_context_json()?;
+
+        // A single entry stays an object; only several entries become an array.
+        let debug_value = match err.debug_infos() {
+            _ if !include_debug => None,
+            [] => None,
+            [info] => Some(serde_json::to_value(info)),
+            infos => Some(serde_json::to_value(infos)),
+        }
+        .transpose()?;
+
+        Ok(Problem {
+            problem_type: Cow::Borrowed(head.problem_type),
+            title: Cow::Borrowed(head.title),
+            status: head.status,
+            detail,
+            instance: err.instance().map(String::from),
+            trace_id: err.trace_id().map(String::from),
+            code,
+            error_code: err.code().map(String::from),
+            context,
+            debug: debug_value,
+            extensions: HashMap::new(),
+        })
+    }
+}
+
+#[cfg(feature = "xml")]
+fn write_xml_element(out: &mut String, name: &str, value: &serde_json::Value) {
+    let tag = if is_xml_name(name) {
+        out.push_str(&format!("<{name}>"));
+        name
+    } else {
+        out.push_str("<i name=\"");
+        push_xml_escaped(out, name);
+        out.push_str("\">");
+        "i"
+    };
+    match value {
+        serde_json::Value::Null => {}
+        serde_json::Value::Bool(b) => out.push_str(&b.to_string()),
+        serde_json::Value::Number(n) => out.push_str(&n.to_string()),
+        serde_json::Value::String(text) => push_xml_escaped(out, text),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                write_xml_element(out, "i", item);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, member) in map {
+                write_xml_element(out, key, member);
+            }
+        }
+    }
+    out.push_str(&format!("</{tag}>"));
+}
+
+/// Whether `name` matches the XML 1.0 `Name` production, minus `:` so no
+/// namespace prefix can be smuggled in.
+#[cfg(feature = "xml")]
+fn is_xml_name(name: &str) -> bool {
+    fn is_start(c: char) -> bool {
+        matches!(c,
+            'A'..='Z' | '_' | 'a'..='z' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+            | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}'
+            | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}'
+            | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
+    }
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_start)
+        && chars.all(|c| {
+            is_start(c)
+                || matches!(c, '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+        })
+}
+
+#[cfg(feature = "xml")]
+fn push_xml_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            // Not an XML 1.0 `Char`, and not representable as a reference either.
+            '\u{0}'..='\u{8}' | '\u{B}' | '\u{C}' | '\u{E}'..='\u{1F}' | '\u{FFFE}' | '\u{FFFF}' => {}
+            c => out.push(c),
+        }
+    }
+}
+
+/// Scrubs secrets from debug text before it is embedded in a `Problem`,
+/// see [`Problem::from_error_debug_with`].
+pub trait DebugRedactor {
+    fn redact(&self, detail: &str) -> String;
+}
+
+impl<F: Fn(&str) -> String> DebugRedactor for F {
+    fn redact(&self, detail: &str) -> String {
+        self(detail)
+    }
+}
+
+/// Regex-based [`DebugRedactor`]. The default masks the values of
+/// `password=`, `passwd=`, `pwd=`, `secret=` and `token=` pairs.
+#[cfg(feature = "regex-redactor")]
+#[derive(Debug, Clone)]
+pub struct RegexRedactor {
+    pattern: regex::Regex,
+    replacement: String,
+}
+
+#[cfg(feature = "regex-redactor")]
+impl RegexRedactor {
+    /// `replacement` may reference capture groups, as in [`regex::Regex::replace_all`].
+    pub fn new(pattern: regex::Regex, replacement: impl Into<String>) -> Self {
+        Self {
+            pattern,
+            replacement: replacement.into(),
+        }
+    }
+}
+
+#[cfg(feature = "regex-redactor")]
+impl Default for RegexRedactor {
+    fn default() -> Self {
+        let pattern = regex::Regex::new(r"(?i)\b(password|passwd|pwd|secret|token)=[^\s;&]+")
+            .expect("default redaction pattern should compile");
+        Self::new(pattern, "${1}=***")
+    }
+}
+
+#[cfg(feature = "regex-redactor")]
+impl DebugRedactor for RegexRedactor {
+    fn redact(&self, detail: &str) -> String {
+        self.pattern.replace_all(detail, self.replacement.as_str()).into_owned()
+    }
+}
+
+/// Per-category HTTP status overrides, e.g. `FailedPrecondition` → 412.
+/// The default policy has no overrides and reproduces
+/// [`CanonicalError::status_code`] exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusCodePolicy {
+    overrides: HashMap<Category, u16>,
+}
+
+impl StatusCodePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_override(mut self, category: Category, status: u16) -> Self {
+        self.overrides.insert(category, status);
+        self
+    }
+
+    /// Returns the override for `err`'s category, falling back to
+    /// `err.status_code()`.
+    pub fn status_code(&self, err: &CanonicalError) -> u16 {
+        self.overrides
+            .get(&err.category())
+            .copied()
+            .unwrap_or_else(|| err.status_code())
+    }
+}
+
+/// The allocation-free part of a `Problem`, for classifying responses
+/// (metrics, HEAD requests) without serializing the context.
+/// See [`CanonicalError::problem_head`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProblemHead {
+    pub status: u16,
+    pub title: &'static str,
+    pub problem_type: &'static str,
+}
+
+/// Request details known at response time, used by
+/// [`Problem::from_error_with_context`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProblemContext {
+    pub path: String,
+    pub method: String,
+    pub trace_id: Option<String>,
+}
+
+impl ProblemContext {
+    pub fn new(method: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            method: method.into(),
+            trace_id: None,
+        }
+    }
+
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = Some(trace_id.into());
+        self
+    }
+}
+
+impl From<CanonicalError> for Problem {
+    fn from(err: CanonicalError) -> Self {
+        Problem::from_error(err)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Problem → CanonicalError (deserialization / round-trip)
+// ---------------------------------------------------------------------------
+
+/// Error returned when a `Problem` cannot be converted into a `CanonicalError`.
+#[derive(Debug)]
+pub enum ProblemConversionError {
+    /// The `type` URI does not have the expected GTS prefix format.
+    InvalidType(String),
+    /// The category extracted from the `type` URI is not one of the 16 known categories.
+    UnknownCategory(String),
+    /// The `context` JSON could not be deserialized into the expected struct for this category.
+    ContextDeserializationFailed {
+        category: String,
+        source: serde_json::Error,
+    },
+    /// The JSON value is not a well-formed `Problem` object.
+    InvalidProblem(serde_json::Error),
+    /// The `status` is not the canonical status code of the category named by `type`.
+    StatusMismatch {
+        category: String,
+        expected: u16,
+        actual: u16,
+    },
+    /// `code` names a different category than `type`.
+    CategoryMismatch { code: String, category: String },
+}
+
+impl fmt::Display for ProblemConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidType(t) => write!(f, "invalid GTS type URI: {t}"),
+            Self::UnknownCategory(c) => write!(f, "unknown canonical error category: {c}"),
+            Self::ContextDeserializationFailed { category, source } => {
+                write!(f, "failed to deserialize context for {category}: {source}")
+            }
+            Self::InvalidProblem(source) => write!(f, "invalid problem JSON: {source}"),
+            Self::StatusMismatch { category, expected, actual } => {
+                write!(f, "status {actual} does not match {category} (expected {expected})")
+            }
+            Self::CategoryMismatch { code, category } => {
+                write!(f, "code {code} does not match the {category} category named by type")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProblemConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ContextDeserializationFailed { source, .. } | Self::InvalidProblem(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// GTS type URI prefix: `gts.cf.core.errors.err.v1~cf.core.errors.`
+const GTS_TYPE_PREFIX: &str = "gts.cf.core.errors.err.v1~cf.core.errors.";
+/// GTS type URI suffix: `.v1~`
+const GTS_TYPE_SUFFIX: &str = ".v1~";
+
+/// Parses a GTS compound type URI and returns the category name.
+///
+/// Example: `"gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~"` → `"not_found"`
+fn parse_category(problem_type: &str) -> Result<&str, ProblemConversionError> {
+    let without_prefix = problem_type
+        .strip_prefix(GTS_TYPE_PREFIX)
+        .ok_or_else(|| ProblemConversionError::InvalidType(problem_type.to_string()))?;
+    let category = without_prefix
+        .strip_suffix(GTS_TYPE_SUFFIX)
+        .ok_or_else(|| ProblemConversionError::InvalidType(problem_type.to_string()))?;
+    Ok(category)
+}
+
+/// Resolves the category a `Problem` names: `code` when present, otherwise
+/// its `type`, see [`problem_type_category`].
+fn resolve_problem_category(problem: &Problem) -> Result<Category, ProblemConversionError> {
+    match problem.code.as_deref() {
+        Some(code) => category_by_name(code),
+        None => problem_type_category(problem),
+    }
+}
+
+/// Resolves the category named by the `type` URI, falling back to the
+/// `gts_type` extension left by [`Problem::with_type_url`].
+fn problem_type_category(problem: &Problem) -> Result<Category, ProblemConversionError> {
+    let name = parse_category(&problem.problem_type).or_else(|err| {
+        match problem.extensions.get("gts_type").and_then(serde_json::Value::as_str) {
+            Some(gts_type) => parse_category(gts_type),
+            None => Err(err),
+        }
+    })?;
+    category_by_name(name)
+}
+
+/// Like [`Category::from_name`], also accepting the legacy `unavailable` spelling.
+fn category_by_name(name: &str) -> Result<Category, ProblemConversionError> {
+    match name {
+        "unavailable" => Ok(Category::ServiceUnavailable),
+        name => Category::from_name(name).ok_or_else(|| ProblemConversionError::UnknownCategory(name.to_string())),
+    }
+}
+
+/// Extracts `resource_type` from a context JSON value (if present) as `Option<String>`.
+fn extract_resource_type(context: &serde_json::Value) -> Option<String> {
+    context
+        .get("resource_type")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Removes the `resource_type` key that `Problem::build` injects into the context,
+/// unless the category's context type (`ResourceInfo`) owns that field itself.
+/// Keeps typed deserialization independent of whether unknown fields are tolerated,
+/// matching the `additionalProperties: false` of the context schemas.
+fn strip_injected_resource_type(category: &str, context: &mut serde_json::Value) {
+    if matches!(category, "not_found" | "already_exists" | "data_loss") {
+        return;
+    }
+    if let Some(map) = context.as_object_mut() {
+        map.remove("resource_type");
+    }
+}
+
+/// Deserializes a typed context from a `serde_json::Value`, mapping errors to `ProblemConversionError`.
+fn deser_ctx<T: DeserializeOwned>(
+    context: serde_json::Value,
+    category: &str,
+) -> Result<T, ProblemConversionError> {
+    serde_json::from_value(context).map_err(|source| ProblemConversionError::ContextDeserializationFailed {
+        category: category.to_string(),
+        source,
+    })
+}
+
+/// Registers every canonical status code with the `Problem` schema, so
+/// handlers can declare `responses(CanonicalError)` in `#[utoipa::path]`.
+#[cfg(feature = "utoipa")]
+impl utoipa::IntoResponses for CanonicalError {
+    fn responses() -> std::collections::BTreeMap<String, utoipa::openapi::RefOr<utoipa::openapi::Response>> {
+        use utoipa::openapi::{ContentBuilder, Ref, ResponseBuilder};
+
+        let mut responses = std::collections::BTreeMap::new();
+        for category in Category::ALL {
+            let status = category.status_code();
+            responses.entry(status.to_string()).or_insert_with(|| {
+                ResponseBuilder::new()
+                    .description(http_reason_phrase(status).unwrap_or_default())
+                    .content(
+                        PROBLEM_JSON_CONTENT_TYPE,
+                        ContentBuilder::new()
+                            .schema(Some(Ref::from_schema_name("Problem")))
+                            .build(),
+                    )
+                    .build()
+                    .into()
+            });
+        }
+        responses
+    }
+}
+
+impl CanonicalError {
+    /// Rebuilds a `CanonicalError` from its category name and untyped context JSON.
+    fn from_category_parts(
+        category: &str,
+        context: serde_json::Value,
+        meta: ErrorMeta,
+    ) -> Result<Self, ProblemConversionError> {
+        // Older producers spelled `service_unavailable` as `unavailable`.
+        let parsed = match category {
+            "unavailable" => Some(Category::ServiceUnavailable),
+            name => Category::from_name(name),
+        };
+        let Some(parsed) = parsed else {
+            return Err(ProblemConversionError::UnknownCategory(category.to_string()));
+        };
+        match parsed {
+            Category::Cancelled => Ok(Self::Cancelled { ctx: deser_ctx(context, category)?, meta, reason: None }),
+            Category::Unknown => Ok(Self::Unknown { ctx: deser_ctx(context, category)?, meta, status: None }),
+            Category::InvalidArgument => Ok(Self::InvalidArgument { ctx: deser_ctx(context, category)?, meta }),
+            Category::DeadlineExceeded => Ok(Self::DeadlineExceeded { ctx: deser_ctx(context, category)?, meta }),
+            Category::NotFound => Ok(Self::NotFound { ctx: deser_ctx(context, category)?, meta }),
+            Category::AlreadyExists => Ok(Self::AlreadyExists { ctx: deser_ctx(context, category)?, meta }),
+            Category::PermissionDenied => Ok(Self::PermissionDenied { ctx: deser_ctx(context, category)?, meta }),
+            Category::ResourceExhausted => Ok(Self::ResourceExhausted { ctx: deser_ctx(context, category)?, meta }),
+            Category::FailedPrecondition => Ok(Self::FailedPrecondition { ctx: deser_ctx(context, category)?, meta }),
+            Category::Aborted => Ok(Self::Aborted { ctx: deser_ctx(context, category)?, meta }),
+            Category::OutOfRange => Ok(Self::OutOfRange { ctx: deser_ctx(context, category)?, meta }),
+            Category::Unimplemented => Ok(Self::Unimplemented { ctx: deser_ctx(context, category)?, meta }),
+            Category::Internal => Ok(Self::Internal { ctx: deser_ctx(context, category)?, meta }),
+            Category::ServiceUnavailable => Ok(Self::ServiceUnavailable { ctx: deser_ctx(context, category)?, meta }),
+            Category::DataLoss => Ok(Self::DataLoss { ctx: deser_ctx(context, category)?, meta }),
+            Category::Unauthenticated => Ok(Self::Unauthenticated { ctx: deser_ctx(context, category)?, meta }),
+        }
+    }
+}
+
+impl TryFrom<Problem> for CanonicalError {
+    type Error = ProblemConversionError;
+
+    fn try_from(problem: Problem) -> Result<Self, Self::Error> {
+        let category = resolve_problem_category(&problem)?.name();
+        let resource_type = extract_resource_type(&problem.context);
+        // `debug` is an object for one entry and an array for several.
+        let debug_info: Vec<DebugInfo> = match problem.debug {
+            None => Ok(Vec::new()),
+            Some(value @ serde_json::Value::Array(_)) => serde_json::from_value(value),
+            Some(value) => serde_json::from_value(value).map(|info| vec![info]),
+        }
+        .map_err(|source| ProblemConversionError::ContextDeserializationFailed {
+            category: category.to_string(),
+            source,
+        })?;
+        let meta = ErrorMeta {
+            message: problem.detail,
+            resource_type,
+            debug_info,
+            instance: problem.instance,
+            trace_id: problem.trace_id,
+            code: problem.error_code,
+            ..ErrorMeta::default()
+        };
+        let mut context = problem.context;
+        strip_injected_resource_type(category, &mut context);
+
+        let mut err = CanonicalError::from_category_parts(category, context, meta)?;
+        if let CanonicalError::Unknown { status, .. } = &mut err
+            && problem.status != 500
+            && (400..=599).contains(&problem.status)
+        {
+            *status = Some(problem.status);
+        }
+        Ok(err)
+    }
+}
+
+impl TryFrom<serde_json::Value> for CanonicalError {
+    type Error = ProblemConversionError;
+
+    /// Interprets `value` as a `Problem`, then converts it as `TryFrom<Problem>` does.
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        let problem: Problem =
+            serde_json::from_value(value).map_err(ProblemConversionError::InvalidProblem)?;
+        CanonicalError::try_from(problem)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// CanonicalError serde (tagged form described by `gts_schema_with_refs`)
+// ---------------------------------------------------------------------------
+
+#[derive(Serialize)]
+struct TaggedErrorRef<'a> {
+    category: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_type: Option<&'a str>,
+    context: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct TaggedError {
+    category: String,
+    message: String,
+    resource_type: Option<String>,
+    context: serde_json::Value,
+}
+
+/// Serializes as `{category, message, resource_type, context}`.
+/// `debug_info` is never part of the tagged form.
+impl Serialize for CanonicalError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TaggedErrorRef {
+            category: self.category_name(),
+            message: self.message(),
+            resource_type: self.resource_type(),
+            context: self.context_value(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CanonicalError {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tagged = TaggedError::deserialize(deserializer)?;
+        let meta = ErrorMeta {
+            resource_type: tagged.resource_type,
+            ..ErrorMeta::new(tagged.message)
+        };
+        CanonicalError::from_category_parts(&tagged.category, tagged.context, meta)
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Schema registry / OpenAPI components
+// ---------------------------------------------------------------------------
+
+const ALL_SCHEMA_IDS: &[&str] = &[
+    RetryInfoV1::SCHEMA_ID,
+    RequestInfoV1::SCHEMA_ID,
+    ResourceInfoV1::SCHEMA_ID,
+    ErrorInfoV1::SCHEMA_ID,
+    FieldViolationV1::SCHEMA_ID,
+    DebugInfoV1::SCHEMA_ID,
+    QuotaViolationV1::SCHEMA_ID,
+    QuotaFailureV1::SCHEMA_ID,
+    PreconditionViolationV1::SCHEMA_ID,
+    PreconditionFailureV1::SCHEMA_ID,
+    Validation::SCHEMA_ID,
+    CanonicalError::SCHEMA_ID,
+];
+
+/// Every GTS schema ID the crate defines: the context schemas (including
+/// `validation.v1`) followed by `canonical_error.v1`.
+pub fn all_schema_ids() -> &'static [&'static str] {
+    ALL_SCHEMA_IDS
+}
+
+/// Pairs each of [`all_schema_ids`] with its `gts_schema_with_refs()` output.
+pub fn all_schemas() -> Vec<(&'static str, serde_json::Value)> {
+    gts_components()
+        .into_iter()
+        .map(|(_, id, schema)| (id, schema))
+        .collect()
+}
+
+/// Every GTS schema the crate defines, as `(component name, schema ID, schema)`
+/// triples in [`all_schema_ids`] order.
+fn gts_components() -> Vec<(&'static str, &'static str, serde_json::Value)> {
+    vec![
+        ("RetryInfoV1", RetryInfoV1::SCHEMA_ID, RetryInfoV1::gts_schema_with_refs()),
+        ("RequestInfoV1", RequestInfoV1::SCHEMA_ID, RequestInfoV1::gts_schema_with_refs()),
+        ("ResourceInfoV1", ResourceInfoV1::SCHEMA_ID, ResourceInfoV1::gts_schema_with_refs()),
+        ("ErrorInfoV1", ErrorInfoV1::SCHEMA_ID, ErrorInfoV1::gts_schema_with_refs()),
+        ("FieldViolationV1", FieldViolationV1::SCHEMA_ID, FieldViolationV1::gts_schema_with_refs()),
+        ("DebugInfoV1", DebugInfoV1::SCHEMA_ID, DebugInfoV1::gts_schema_with_refs()),
+        ("QuotaViolationV1", QuotaViolationV1::SCHEMA_ID, QuotaViolationV1::gts_schema_with_refs()),
+        ("QuotaFailureV1", QuotaFailureV1::SCHEMA_ID, QuotaFailureV1::gts_schema_with_refs()),
+        (
+            "PreconditionViolationV1",
+            PreconditionViolationV1::SCHEMA_ID,
+            PreconditionViolationV1::gts_schema_with_refs(),
+        ),
+        (
+            "PreconditionFailureV1",
+            PreconditionFailureV1::SCHEMA_ID,
+            PreconditionFailureV1::gts_schema_with_refs(),
+        ),
+        ("Validation", Validation::SCHEMA_ID, Validation::gts_schema_with_refs()),
+        ("CanonicalError", CanonicalError::SCHEMA_ID, CanonicalError::gts_schema_with_refs()),
+    ]
+}
+
+fn problem_openapi_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "type": { "type": "string", "format": "uri-reference" },
+            "title": { "type": "string" },
+            "status": { "type": "integer", "format": "uint16", "minimum": 100, "maximum": 599 },
+            "detail": { "type": "string" },
+            "instance": { "type": "string" },
+            "trace_id": { "type": "string" },
+            "code": { "type": "string", "description": "Category name, e.g. `not_found`" },
+            "error_code": {
+                "type": "string",
+                "description": "Symbolic code set with `CanonicalError::with_code`, e.g. `USER_NOT_FOUND`"
+            },
+            "context": { "type": "object" },
+            "debug": {
+                "oneOf": [
+                    { "$ref": "#/components/schemas/DebugInfoV1" },
+                    { "type": "array", "items": { "$ref": "#/components/schemas/DebugInfoV1" } }
+                ]
+            }
+        },
+        "required": ["type", "title", "status", "detail", "context"],
+        "additionalProperties": true
+    })
+}
+
+/// Returns `{schema_name: json_schema}` for `Problem`, `CanonicalError` and
+/// every context type, ready to merge into an OpenAPI `components.schemas`
+/// object.
+///
+/// GTS schemas are taken from `gts_schema_with_refs` with `$id` and
+/// `$schema` removed, and both `gts://<schema id>` and `#/$defs/<name>`
+/// references rewritten to `#/components/schemas/<name>`. Schema names are
+/// the Rust type names (`ResourceInfoV1`, `Validation`, ...).
+pub fn openapi_components() -> serde_json::Value {
+    let components = gts_components();
+    let names_by_id: HashMap<String, &'static str> = components
+        .iter()
+        .map(|(name, id, _)| (format!("gts://{id}"), *name))
+        .collect();
+
+    let mut out = serde_json::Map::new();
+    for (name, _, mut schema) in components {
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$id");
+            obj.remove("$schema");
+        }
+        rewrite_openapi_refs(&mut schema, name, &names_by_id);
+        out.insert(name.to_string(), schema);
+    }
+    out.insert("Problem".to_string(), problem_openapi_schema());
+    serde_json::Value::Object(out)
+}
+
+/// `"$ref": "#"` is a self-reference (e.g. `ResourceInfo.parent`) and
+/// resolves to `self_name`.
+fn rewrite_openapi_refs(value: &mut serde_json::Value, self_name: &str, names_by_id: &HashMap<String, &'static str>) {
+    match value {
+        serde_json::Value::Object(obj) => {
+            for (key, child) in obj.iter_mut() {
+                if key == "$ref"
+                    && let Some(target) = child.as_str()
+                {
+                    let name = match target {
+                        "#" => Some(self_name),
+                        _ => names_by_id.get(target).copied().or_else(|| target.strip_prefix("#/$defs/")),
+                    };
+                    if let Some(name) = name {
+                        *child = serde_json::Value::String(format!("#/components/schemas/{name}"));
+                    }
+                } else {
+                    rewrite_openapi_refs(child, self_name, names_by_id);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                rewrite_openapi_refs(item, self_name, names_by_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// google.rpc.* wire compatibility
+// ---------------------------------------------------------------------------
+
+/// Parsers for `google.rpc.*` error detail JSON (proto3 JSON mapping).
+///
+/// Keys are accepted in both the camelCase form emitted by proto3 JSON
+/// (`fieldViolations`) and the original snake_case field names. Fields omitted
+/// by proto3 default-value elision deserialize as empty strings.
+pub mod google_compat {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct BadRequest {
+        #[serde(default, rename = "fieldViolations", alias = "field_violations")]
+        field_violations: Vec<GoogleFieldViolation>,
+    }
+
+    #[derive(Deserialize)]
+    struct GoogleFieldViolation {
+        #[serde(default)]
+        field: String,
+        #[serde(default)]
+        description: String,
+        #[serde(default)]
+        reason: String,
+    }
+
+    #[derive(Deserialize)]
+    struct GoogleQuotaFailure {
+        #[serde(default)]
+        violations: Vec<GoogleQuotaViolation>,
+    }
+
+    #[derive(Deserialize)]
+    struct GoogleQuotaViolation {
+        #[serde(default)]
+        subject: String,
+        #[serde(default)]
+        description: String,
+    }
+
+    #[derive(Deserialize)]
+    struct GooglePreconditionFailure {
+        #[serde(default)]
+        violations: Vec<GooglePreconditionViolation>,
+    }
+
+    #[derive(Deserialize)]
+    struct GooglePreconditionViolation {
+        #[serde(default, rename = "type")]
+        precondition_type: String,
+        #[serde(default)]
+        subject: String,
+        #[serde(default)]
+        description: String,
+    }
+
+    /// Parses a `google.rpc.BadRequest` into `Validation::FieldViolations`.
+    pub fn bad_request(value: &serde_json::Value) -> Result<Validation, serde_json::Error> {
+        let parsed = BadRequest::deserialize(value)?;
+        Ok(Validation::fields(
+            parsed
+                .field_violations
+                .into_iter()
+                .map(|v| FieldViolation::new(v.field, v.description, v.reason))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Parses a `google.rpc.QuotaFailure`.
+    pub fn quota_failure(value: &serde_json::Value) -> Result<QuotaFailure, serde_json::Error> {
+        let parsed = GoogleQuotaFailure::deserialize(value)?;
+        Ok(QuotaFailure::new(
+            parsed
+                .violations
+                .into_iter()
+                .map(|v| QuotaViolation::new(v.subject, v.description))
+                .collect::<Vec<_>>(),
+        ))
+    }
+
+    /// Parses a `google.rpc.PreconditionFailure`.
+    pub fn precondition_failure(
+        value: &serde_json::Value,
+    ) -> Result<PreconditionFailure, serde_json::Error> {
+        let parsed = GooglePreconditionFailure::deserialize(value)?;
+        Ok(PreconditionFailure::new(
+            parsed
+                .violations
+                .into_iter()
+                .map(|v| PreconditionViolation::new(v.precondition_type, v.subject, v.description))
+                .collect::<Vec<_>>(),
+        ))
+    }
+}
+
+impl Validation {
+    /// Parses a camelCase `google.rpc.BadRequest` JSON object.
+    /// See [`google_compat`] for the accepted shape.
+    pub fn from_google_bad_request(value: &serde_json::Value) -> Result<Self, serde_json::Error> {
+        google_compat::bad_request(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_types_are_deserializable() {
+        // FieldViolation
+        let json = r#"{"field":"email","description":"is required","reason":"REQUIRED"}"#;
+        let fv: FieldViolation = serde_json::from_str(json).unwrap();
+        assert_eq!(fv.field, "email");
+        assert_eq!(fv.description, "is required");
+        assert_eq!(fv.reason, "REQUIRED");
+
+        // ResourceInfo
+        let json = r#"{"resource_type":"gts.cf.core.users.user.v1","resource_name":"user-123","description":"Resource not found"}"#;
+        let ri: ResourceInfo = serde_json::from_str(json).unwrap();
+        assert_eq!(ri.resource_name, "user-123");
+    }
+
+    #[test]
+    fn resource_info_composite_joins_parts() {
+        let ri = ResourceInfo::composite("gts.cf.core.users.user.v1", &["tenant-1", "user-9"]);
+        assert_eq!(ri.resource_type, "gts.cf.core.users.user.v1");
+        assert_eq!(ri.resource_name, "tenant-1/user-9");
+
+        let single = ResourceInfo::composite("gts.cf.core.users.user.v1", &["user-9"]);
+        assert_eq!(single.resource_name, "user-9");
+    }
+
+    #[test]
+    fn not_found_gts_type() {
+        let err =
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
+        assert_eq!(
+            err.gts_type(),
+            "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~"
+        );
+    }
+
+    #[test]
+    fn not_found_status_code() {
+        let err =
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
+        assert_eq!(err.status_code(), 404);
+    }
+
+    #[test]
+    fn not_found_title() {
+        let err =
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
+        assert_eq!(err.title(), "Not Found");
+    }
+
+    #[test]
+    fn status_line_standard_and_non_standard_codes() {
+        let not_found = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert_eq!(not_found.status_line(), "404 Not Found");
+
+        let cancelled = CanonicalError::cancelled(RequestInfo::new("req-1"));
+        assert_eq!(cancelled.status_line(), "499 Client Closed Request");
+
+        let teapot = CanonicalError::unknown_with_status("brewing", 418);
+        assert_eq!(teapot.reason_phrase(), None);
+        assert_eq!(teapot.status_line(), "418");
+    }
+
+    #[test]
+    fn canonical_error_eq_compares_meaningful_fields() {
+        let a = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert_eq!(a, CanonicalError::not_found(ResourceInfo::new("t", "n")));
+        assert_ne!(a, CanonicalError::not_found(ResourceInfo::new("t", "other")));
+        assert_ne!(a, a.clone().with_message("custom"));
+        assert_ne!(a, CanonicalError::already_exists(ResourceInfo::new("t", "n")));
+    }
+
+    #[test]
+    fn canonical_error_eq_distinguishes_debug_info() {
+        let base = CanonicalError::internal(DebugInfo::new("bug"));
+        let with_debug = base.clone().with_debug_info(DebugInfo::new("detail"));
+        assert_ne!(base, with_debug);
+        assert_ne!(with_debug, base.clone().with_debug_info(DebugInfo::new("other detail")));
+    }
+
+    #[test]
+    fn canonical_error_eq_survives_problem_roundtrip() {
+        let original = CanonicalError::permission_denied(
+            ErrorInfo::new("CROSS_TENANT_ACCESS", "auth.cyberfabric.io").with_metadata("tenant_id", "t-1"),
+        );
+        let reconstructed = CanonicalError::try_from(Problem::from_error(original.clone())).unwrap();
+        assert_eq!(original, reconstructed);
+    }
+
+    #[test]
+    fn into_err_and_into_problem_err() {
+        let err = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+
+        let result: Result<u32, CanonicalError> = err.clone().into_err();
+        assert_eq!(result.unwrap_err(), err);
+
+        let result: Result<u32, Problem> = err.into_problem_err();
+        let problem = result.unwrap_err();
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.context["resource_name"], "n");
+    }
+
+    #[test]
+    fn into_parts_returns_owned_components() {
+        #[resource_error("gts.cf.core.users.user.v1")]
+        struct UserResourceError;
+
+        let err = UserResourceError::not_found("user-123")
+            .with_message("User not found")
+            .with_debug_info(DebugInfo::new("0 rows"));
+        let parts = err.into_parts();
+        assert_eq!(parts.category, Category::NotFound);
+        assert_eq!(parts.meta.message, "User not found");
+        assert_eq!(parts.meta.resource_type.as_deref(), Some("gts.cf.core.users.user.v1"));
+        assert_eq!(parts.meta.debug_info[0].detail, "0 rows");
+        match parts.context {
+            OwnedErrorContext::ResourceInfo(ri) => assert_eq!(ri.resource_name, "user-123"),
+            other => panic!("expected ResourceInfo, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn meta_mut_updates_the_shared_fields_builders_read() {
+        let mut err = CanonicalError::aborted(ErrorInfo::new("VERSION_CONFLICT", "orders"))
+            .with_trace_id("trace-1")
+            .with_code("ORDER_CONFLICT");
+        assert_eq!(err.meta().trace_id.as_deref(), Some("trace-1"));
+        assert_eq!(err.meta().code.as_deref(), Some("ORDER_CONFLICT"));
+
+        err.meta_mut().message = String::from("Order was modified concurrently");
+        err.meta_mut().instance = Some(String::from("/orders/42"));
+        assert_eq!(err.message(), "Order was modified concurrently");
+        assert_eq!(err.instance(), Some("/orders/42"));
+    }
+
+    #[test]
+    fn from_parts_reverses_into_parts() {
+        let original = CanonicalError::resource_exhausted(QuotaFailure::new([QuotaViolation::new(
+            "requests_per_minute",
+            "Limit exceeded",
+        )]))
+        .with_resource_type("gts.cf.core.users.user.v1");
+        let rebuilt = CanonicalError::from_parts(original.clone().into_parts()).unwrap();
+        assert_eq!(original, rebuilt);
+    }
+
+    #[test]
+    fn from_parts_rejects_mismatched_context() {
+        let mut parts = CanonicalError::not_found(ResourceInfo::new("t", "n")).into_parts();
+        parts.category = Category::PermissionDenied;
+        let returned = CanonicalError::from_parts(parts).unwrap_err();
+        assert_eq!(returned.category, Category::PermissionDenied);
+    }
+
+    #[test]
+    fn unknown_with_status_preserves_upstream_status() {
+        let err = CanonicalError::unknown_with_status("upstream returned 502", 502);
+        assert_eq!(err.status_code(), 502);
+        assert_eq!(err.grpc_code(), 2);
+        let problem = Problem::from(err.clone());
+        assert_eq!(problem.status, 502);
+        assert_eq!(serde_json::to_value(&problem).unwrap()["status"], 502);
+
+        let reconstructed = CanonicalError::try_from(problem).unwrap();
+        assert_eq!(reconstructed, err);
+    }
+
+    #[test]
+    fn unknown_with_status_keeps_only_error_statuses() {
+        for status in [0, 42, 200, 304, 399, 600, 1000] {
+            let err = CanonicalError::unknown_with_status("upstream", status);
+            assert_eq!(err.status_code(), 500, "status {status}");
+            assert_eq!(Problem::from(err).status, 500);
+        }
+        assert_eq!(CanonicalError::unknown_with_status("upstream", 400).status_code(), 400);
+        assert_eq!(CanonicalError::unknown_with_status("upstream", 599).status_code(), 599);
+        assert_eq!(CanonicalError::from_status_code(200, "ok?").status_code(), 500);
+
+        let mut problem = Problem::from(CanonicalError::unknown("upstream"));
+        problem.status = 200;
+        assert_eq!(CanonicalError::try_from(problem).unwrap().status_code(), 500);
+    }
+
+    #[test]
+    fn unknown_default_status_is_500() {
+        let err = CanonicalError::unknown("boom");
+        assert_eq!(err.status_code(), 500);
+        let reconstructed = CanonicalError::try_from(Problem::from(err.clone())).unwrap();
+        assert_eq!(reconstructed, err);
+    }
+
+    #[test]
+    fn category_classification_matches_error_accessors() {
+        let errors = sample_error_per_category();
+        let mut seen = std::collections::HashSet::new();
+        for err in &errors {
+            let category = err.category();
+            assert!(seen.insert(category), "duplicate category {category:?}");
+            assert_eq!(category.status_code(), err.status_code());
+            assert_eq!(category.grpc_code(), err.grpc_code());
+            assert_eq!(category.title(), err.title());
+            assert_eq!(category.gts_type(), err.gts_type());
+        }
+        assert_eq!(seen.len(), 16);
+    }
+
+    #[test]
+    fn category_status_code_ignores_unknown_override() {
+        let err = CanonicalError::unknown_with_status("upstream", 502);
+        assert_eq!(err.category(), Category::Unknown);
+        assert_eq!(err.category().status_code(), 500);
+        assert_eq!(err.status_code(), 502);
+    }
+
+    #[test]
+    fn insert_metadata_enriches_error_info_variants() {
+        let mut err = CanonicalError::aborted(ErrorInfo::new("CONFLICT", "svc"));
+        assert!(err.insert_metadata("attempt_count", "3"));
+        assert_eq!(err.metadata().unwrap()["attempt_count"], "3");
+
+        let problem = Problem::from(err);
+        assert_eq!(problem.context["metadata"]["attempt_count"], "3");
+    }
+
+    #[test]
+    fn insert_metadata_is_noop_without_error_info() {
+        let mut err = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert!(!err.insert_metadata("k", "v"));
+        assert!(err.metadata().is_none());
+    }
+
+    #[test]
+    fn localize_replaces_duplicate_locale() {
+        let err = CanonicalError::not_found(ResourceInfo::new("t", "n"))
+            .localize("fr", "Ressource introuvable")
+            .localize("de", "Ressource nicht gefunden")
+            .localize("fr", "Ressource absente");
+        assert_eq!(err.localized_message("fr"), Some("Ressource absente"));
+        assert_eq!(err.localized_message("de"), Some("Ressource nicht gefunden"));
+        assert_eq!(err.localized_message("es"), None);
+        assert_eq!(err.localized_messages().len(), 2);
+        assert_eq!(err.message(), "Resource not found");
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn problem_json_schema_uses_wire_field_names() {
+        let schema = serde_json::to_value(schemars::schema_for!(Problem)).unwrap();
+        let props = schema["properties"].as_object().unwrap();
+        assert!(props.contains_key("type"));
+        assert!(!props.contains_key("problem_type"));
+
+        let mut required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap())
+            .collect();
+        required.sort();
+        assert_eq!(required, vec!["context", "detail", "status", "title", "type"]);
+    }
+
+    #[test]
+    fn error_info_metadata_serializes_in_key_order() {
+        let info = ErrorInfo::new("R", "D")
+            .with_metadata("zeta", "1")
+            .with_metadata("alpha", "2")
+            .with_metadata("mid", "3");
+        let json = serde_json::to_string(&info).unwrap();
+        assert!(json.contains(r#""metadata":{"alpha":"2","mid":"3","zeta":"1"}"#), "{json}");
+    }
+
+    #[test]
+    fn with_source_exposes_cause_and_fills_debug_detail() {
+        use std::error::Error as _;
+
+        let io_err = io::Error::new(io::ErrorKind::NotFound, "users.db missing");
+        let err = CanonicalError::internal(DebugInfo::new("load user")).with_source(io_err);
+        assert!(err.source().is_some());
+        assert_eq!(err.source().unwrap().to_string(), "users.db missing");
+        assert_eq!(err.debug_info().unwrap().detail, "users.db missing");
+    }
+
+    #[test]
+    fn with_source_keeps_existing_debug_stack() {
+        let io_err = io::Error::other("timeout");
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(1))
+            .with_debug_info(DebugInfo::new("old").with_stack(vec!["frame".into()]))
+            .with_source(io_err);
+        let infos = err.debug_infos();
+        assert_eq!(infos[0].detail, "old");
+        assert_eq!(infos[0].stack_entries, vec!["frame".to_string()]);
+        assert_eq!(infos[1].detail, "timeout");
+    }
+
+    #[cfg(feature = "utoipa")]
+    #[test]
+    fn utoipa_responses_reference_problem_schema() {
+        use utoipa::openapi::RefOr;
+        use utoipa::{IntoResponses, PartialSchema};
+
+        let responses = CanonicalError::responses();
+        for code in ["404", "500"] {
+            let RefOr::T(response) = &responses[code] else {
+                panic!("{code} should be an inline response");
+            };
+            let content = &response.content[PROBLEM_JSON_CONTENT_TYPE];
+            let Some(RefOr::Ref(schema)) = &content.schema else {
+                panic!("{code} should reference the Problem schema");
+            };
+            assert_eq!(schema.ref_location, "#/components/schemas/Problem");
+        }
+        let distinct: std::collections::BTreeSet<u16> =
+            Category::ALL.iter().map(|c| c.status_code()).collect();
+        assert_eq!(responses.len(), distinct.len());
+
+        let schema = serde_json::to_value(Problem::schema()).unwrap();
+        assert!(schema["properties"].get("type").is_some());
+    }
+
+    #[test]
+    fn resource_type_mismatch_detects_divergent_tag() {
+        let ok = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1~", "u-1"));
+        assert_eq!(ok.resource_type_mismatch(), None);
+
+        let bad = CanonicalError::DataLoss {
+            ctx: ResourceInfo::new("gts.cf.core.users.user.v1~", "u-1"),
+            meta: ErrorMeta {
+                resource_type: Some(String::from("gts.cf.core.files.file.v1~")),
+                ..ErrorMeta::new("Data loss detected")
+            },
+        };
+        assert_eq!(
+            bad.resource_type_mismatch(),
+            Some(("gts.cf.core.files.file.v1~", "gts.cf.core.users.user.v1~"))
+        );
+    }
+
+    #[test]
+    fn with_resource_type_allows_mismatch_and_reports_it() {
+        let err = CanonicalError::already_exists(ResourceInfo::new("gts.cf.core.users.user.v1~", "u-1"))
+            .with_resource_type("gts.cf.core.files.file.v1~");
+        assert_eq!(
+            err.resource_type_mismatch(),
+            Some(("gts.cf.core.files.file.v1~", "gts.cf.core.users.user.v1~"))
+        );
+    }
+
+    #[test]
+    fn with_cause_exposes_full_chain() {
+        let cause: Box<dyn std::error::Error + Send + Sync> = "connection reset".into();
+        let err = CanonicalError::unknown("upstream failed").with_cause(cause);
+        assert!(err.debug_info().is_none());
+
+        let chain = DebugInfo::from_error_chain(&err);
+        assert_eq!(chain.detail, "unknown: upstream failed");
+        assert_eq!(chain.stack_entries, vec!["caused by: connection reset".to_string()]);
+    }
+
+    #[test]
+    fn validation_builder_accumulates_violations() {
+        let mut builder = Validation::builder();
+        assert!(builder.is_empty());
+        builder
+            .push_format("email", "must be a valid email address")
+            .push_constraint("age", "must be at least 18")
+            .push_field("name", "is required", "REQUIRED");
+        assert!(!builder.is_empty());
+
+        let Some(Validation::FieldViolations { field_violations }) = builder.build() else {
+            panic!("expected field violations");
+        };
+        let reasons: Vec<&str> = field_violations.iter().map(|v| v.reason.as_str()).collect();
+        assert_eq!(reasons, vec!["INVALID_FORMAT", "CONSTRAINT_VIOLATION", "REQUIRED"]);
+        assert_eq!(field_violations[1].field, "age");
+    }
+
+    #[test]
+    fn validation_builder_without_violations_builds_none() {
+        assert_eq!(ValidationBuilder::new().build(), None);
+    }
+
+    #[test]
+    fn problem_code_is_preferred_over_type_uri() {
+        let err = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1~", "u-1"))
+            .with_resource_type("gts.cf.core.users.user.v1~");
+        let mut problem = Problem::from(err.clone());
+        assert_eq!(problem.code.as_deref(), Some("not_found"));
+
+        problem.problem_type = "https://errors.example.com/not-found".into();
+        assert_eq!(CanonicalError::try_from(problem).unwrap(), err);
+    }
+
+    #[test]
+    fn symbolic_code_is_emitted_as_error_code_and_round_trips() {
+        let plain = CanonicalError::not_found_resource("t", "u-1");
+        let json = serde_json::to_value(Problem::from(plain.clone())).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert!(json.get("error_code").is_none());
+        assert_eq!(plain.code(), None);
+
+        let err = plain.with_code("USER_NOT_FOUND");
+        let json = serde_json::to_value(Problem::from(err.clone())).unwrap();
+        assert_eq!(json["code"], "not_found");
+        assert_eq!(json["error_code"], "USER_NOT_FOUND");
+        let problem: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(CanonicalError::try_from(problem.clone()).unwrap(), err);
+
+        let back = CanonicalError::try_from(problem.with_type_url("https://errors.example.com")).unwrap();
+        assert_eq!(back, err);
+    }
+
+    #[test]
+    fn symbolic_code_spelling_a_category_name_round_trips() {
+        for (err, code) in [
+            (CanonicalError::permission_denied(ErrorInfo::new("R", "D")).with_code("aborted"), "aborted"),
+            (CanonicalError::not_found_resource("t", "n").with_code("not_found"), "not_found"),
+        ] {
+            let back = CanonicalError::try_from(Problem::from(err.clone())).unwrap();
+            assert_eq!(back.category(), err.category());
+            assert_eq!(back.code(), Some(code));
+            assert_eq!(back, err);
+        }
+    }
+
+    #[test]
+    fn problem_without_code_falls_back_to_type_uri() {
+        let err = CanonicalError::aborted(ErrorInfo::new("CONFLICT", "svc"));
+        let mut problem = Problem::from(err.clone());
+        problem.code = None;
+        let json = serde_json::to_value(&problem).unwrap();
+        assert!(json.get("code").is_none());
+
+        let parsed: Problem = serde_json::from_value(json).unwrap();
+        assert_eq!(CanonicalError::try_from(parsed).unwrap(), err);
+    }
+
+    #[test]
+    fn merge_field_violations_concatenates_in_order() {
+        let first = CanonicalError::invalid_argument(Validation::fields(vec![FieldViolation::new(
+            "email", "invalid", "INVALID_FORMAT",
+        )]));
+        let second = CanonicalError::invalid_argument(Validation::fields(vec![FieldViolation::new(
+            "age", "too young", "OUT_OF_RANGE",
+        )]));
+        let merged = first.merge_field_violations(second).unwrap();
+        let CanonicalError::InvalidArgument {
+            ctx: Validation::FieldViolations { field_violations },
+            ..
+        } = merged
+        else {
+            panic!("expected field violations");
+        };
+        let fields: Vec<&str> = field_violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, vec!["email", "age"]);
+    }
+
+    #[test]
+    fn merge_field_violations_rejects_other_shapes() {
+        let fields = CanonicalError::invalid_argument(Validation::fields(vec![]));
+        let format = CanonicalError::invalid_argument(Validation::format("bad json"));
+        assert_eq!(fields.clone().merge_field_violations(format.clone()), Err(fields.clone()));
+        assert_eq!(format.clone().merge_field_violations(fields.clone()), Err(format));
+
+        let not_found = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert_eq!(fields.clone().merge_field_violations(not_found), Err(fields));
+    }
+
+    #[test]
+    fn merge_metadata_keeps_first_writer() {
+        let err = CanonicalError::permission_denied(ErrorInfo::new("DENIED", "auth").with_metadata("layer", "repo"))
+            .merge_metadata([("layer".to_string(), "service".to_string())])
+            .merge_metadata([
+                ("layer".to_string(), "handler".to_string()),
+                ("route".to_string(), "/users".to_string()),
+            ]);
+        let metadata = err.metadata().unwrap();
+        assert_eq!(metadata["layer"], "repo");
+        assert_eq!(metadata["route"], "/users");
+    }
+
+    #[test]
+    fn try_from_json_value_converts_problem() {
+        let value = serde_json::json!({
+            "type": "gts.cf.core.errors.err.v1~cf.core.errors.aborted.v1~",
+            "title": "Aborted",
+            "status": 409,
+            "detail": "Operation aborted due to concurrency conflict",
+            "context": { "reason": "CONFLICT", "domain": "svc", "metadata": {} }
+        });
+        let err = CanonicalError::try_from(value).unwrap();
+        assert_eq!(err, CanonicalError::aborted(ErrorInfo::new("CONFLICT", "svc")));
+    }
+
+    #[test]
+    fn try_from_json_value_rejects_non_problem() {
+        let err = CanonicalError::try_from(serde_json::json!({ "title": "missing type" })).unwrap_err();
+        assert!(matches!(err, ProblemConversionError::InvalidProblem(_)));
+    }
+
+    #[test]
+    fn category_status_constants_match_patterns() {
+        let err = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        let matched = match err.status_code() {
+            Category::NOT_FOUND_STATUS => "not found",
+            Category::INTERNAL_STATUS => "internal",
+            _ => "other",
+        };
+        assert_eq!(matched, "not found");
+        assert_eq!(Category::CANCELLED_STATUS, 499);
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn into_http_response_sets_status_headers_and_body() {
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(30)).with_trace_id("trace-1");
+        let response: http::Response<String> = err.clone().into_http_response();
+        assert_eq!(response.status(), http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()[http::header::CONTENT_TYPE], "application/problem+json");
+        assert_eq!(response.headers()[http::header::RETRY_AFTER], "30");
+        assert_eq!(response.headers()["x-trace-id"], "trace-1");
+        let problem: Problem = serde_json::from_str(response.body()).unwrap();
+        assert_eq!(CanonicalError::try_from(problem).unwrap(), err);
+
+        let mut problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")));
+        problem.trace_id = Some("bad\nid".to_string());
+        let response: http::Response<Vec<u8>> = problem.into_http_response();
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+        assert!(!response.headers().contains_key("x-trace-id"));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_status_code_from_error_and_category() {
+        for category in Category::ALL {
+            assert_eq!(http::StatusCode::from(category).as_u16(), category.status_code());
+        }
+        let err = CanonicalError::unknown_with_status("upstream", 502);
+        assert_eq!(http::StatusCode::from(&err), http::StatusCode::BAD_GATEWAY);
+        let err = CanonicalError::unknown_with_status("upstream", 42);
+        assert_eq!(http::StatusCode::from(&err), http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn from_status_code_picks_nearest_category() {
+        let cases = [
+            (400, Category::InvalidArgument),
+            (401, Category::Unauthenticated),
+            (403, Category::PermissionDenied),
+            (404, Category::NotFound),
+            (409, Category::AlreadyExists),
+            (429, Category::ResourceExhausted),
+            (500, Category::Internal),
+            (502, Category::Internal),
+            (503, Category::ServiceUnavailable),
+            (504, Category::DeadlineExceeded),
+            (418, Category::Unknown),
+        ];
+        for (code, category) in cases {
+            let err = CanonicalError::from_status_code(code, "upstream said no");
+            assert_eq!(err.category(), category, "status {code}");
+            assert_eq!(err.message(), "upstream said no");
+        }
+        assert_eq!(CanonicalError::from_status_code(418, "teapot").status_code(), 418);
+        assert_eq!(CanonicalError::from_status_code(502, "bad gateway").status_code(), 500);
+
+        let conflict = CanonicalError::from_status_code(409, "duplicate");
+        assert_eq!(conflict.as_already_exists().unwrap().description, "Resource already exists");
+    }
+
+    #[test]
+    fn invalid_field_produces_single_violation() {
+        let problem = Problem::from(CanonicalError::invalid_field("email", "must contain @", "INVALID_FORMAT"));
+        assert_eq!(problem.status, 400);
+        assert_eq!(
+            problem.context["field_violations"],
+            serde_json::json!([
+                { "field": "email", "description": "must contain @", "reason": "INVALID_FORMAT" }
+            ])
+        );
+    }
+
+    #[test]
+    fn quota_failure_builder_accumulates_limits() {
+        let mut builder = QuotaFailure::builder();
+        builder
+            .push("requests_per_minute", "60/min exceeded")
+            .push("requests_per_day", "10000/day exceeded");
+        let failure = builder.build();
+        let subjects: Vec<&str> = failure.violations.iter().map(|v| v.subject.as_str()).collect();
+        assert_eq!(subjects, vec!["requests_per_minute", "requests_per_day"]);
+    }
+
+    #[test]
+    fn precondition_failure_builder_accumulates_violations() {
+        let mut builder = PreconditionFailureBuilder::new();
+        assert!(builder.is_empty());
+        builder.push("STATE", "order/42", "order is already shipped");
+        let failure = builder.build();
+        assert_eq!(failure.violations.len(), 1);
+        assert_eq!(failure.violations[0].precondition_type, "STATE");
+        let expected = PreconditionViolation::new("STATE", "order/42", "order is already shipped");
+        assert_eq!(failure, PreconditionFailure::new(vec![expected]));
+    }
+
+    #[test]
+    fn fingerprint_ignores_request_ids_and_numbers() {
+        let a = CanonicalError::deadline_exceeded(RequestInfo::new("req-1"))
+            .with_message("timed out after 30s");
+        let b = CanonicalError::deadline_exceeded(RequestInfo::new("req-2"))
+            .with_message("timed out after 45s");
+        assert_eq!(a.fingerprint(), b.fingerprint());
+
+        let other = CanonicalError::cancelled(RequestInfo::new("req-1")).with_message("timed out after 30s");
+        assert_ne!(a.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_distinguishes_resource_type() {
+        let user = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1~", "u-1"))
+            .with_resource_type("gts.cf.core.users.user.v1~");
+        let file = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.files.file.v1~", "u-1"))
+            .with_resource_type("gts.cf.core.files.file.v1~");
+        assert_ne!(user.fingerprint(), file.fingerprint());
+    }
+
+    #[test]
+    fn context_borrows_typed_payload() {
+        let quota = QuotaFailure::new(vec![QuotaViolation::new("requests_per_minute", "60/min")]);
+        let err = CanonicalError::resource_exhausted(quota.clone());
+        match err.context() {
+            ErrorContext::QuotaFailure(ctx) => assert_eq!(ctx, &quota),
+            other => panic!("unexpected context {other:?}"),
+        }
+        assert_eq!(err.into_context(), OwnedErrorContext::QuotaFailure(quota));
+    }
+
+    #[test]
+    fn field_violation_reason_serializes_as_plain_string() {
+        let typed = FieldViolation::new("name", "is required", "").with_reason(FieldViolationReason::Required);
+        let raw = FieldViolation::new("name", "is required", "REQUIRED");
+        assert_eq!(serde_json::to_value(&typed).unwrap(), serde_json::to_value(&raw).unwrap());
+        assert_eq!(raw.reason_kind(), FieldViolationReason::Required);
+
+        let custom = FieldViolation::new("sku", "unknown", "NOT_IN_CATALOG");
+        assert_eq!(custom.reason_kind(), FieldViolationReason::Other("NOT_IN_CATALOG".into()));
+    }
+
+    #[test]
+    fn field_violation_reason_serde_roundtrip() {
+        let reasons = vec![FieldViolationReason::TooShort, FieldViolationReason::Other("CUSTOM".into())];
+        let json = serde_json::to_value(&reasons).unwrap();
+        assert_eq!(json, serde_json::json!(["TOO_SHORT", "CUSTOM"]));
+        let back: Vec<FieldViolationReason> = serde_json::from_value(json).unwrap();
+        assert_eq!(back, reasons);
+    }
+
+    #[cfg(feature = "backtrace")]
+    #[test]
+    fn backtrace_fills_stack_entries_only_when_captured() {
+        use std::backtrace::Backtrace;
+
+        let captured = DebugInfo::new("boom").with_backtrace(&Backtrace::force_capture());
+        assert!(!captured.stack_entries.is_empty());
+
+        let disabled = DebugInfo::new("boom").with_backtrace(&Backtrace::disabled());
+        assert!(disabled.stack_entries.is_empty());
+    }
+
+    #[cfg(feature = "tracing")]
+    mod tracing_capture {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        pub type CapturedEvent = (Level, Vec<(String, String)>);
+
+        #[derive(Clone, Default)]
+        pub struct Capture(pub Arc<Mutex<Vec<CapturedEvent>>>);
+
+        struct Fields(Vec<(String, String)>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push((field.name().to_string(), format!("{value:?}")));
+            }
+
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.push((field.name().to_string(), value.to_string()));
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = Fields(Vec::new());
+                event.record(&mut fields);
+                self.0.lock().unwrap().push((*event.metadata().level(), fields.0));
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn emit_tracing_event_levels_and_fields() {
+        let capture = tracing_capture::Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            CanonicalError::internal(DebugInfo::new("db down"))
+                .with_debug_info(DebugInfo::new("pool exhausted").with_stack(vec!["frame".into()]))
+                .emit_tracing_event();
+            CanonicalError::not_found(ResourceInfo::new("t", "n"))
+                .with_resource_type("t")
+                .emit_tracing_event();
+            CanonicalError::cancelled(RequestInfo::new("r")).emit_tracing_event();
+        });
+
+        let events = capture.0.lock().unwrap();
+        let levels: Vec<tracing::Level> = events.iter().map(|(level, _)| *level).collect();
+        assert_eq!(levels, vec![tracing::Level::ERROR, tracing::Level::WARN, tracing::Level::INFO]);
+
+        let field = |i: usize, name: &str| {
+            events[i].1.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+        };
+        assert_eq!(field(0, "category").as_deref(), Some("internal"));
+        assert_eq!(field(0, "status").as_deref(), Some("500"));
+        assert_eq!(field(0, "debug_detail").as_deref(), Some("pool exhausted"));
+        assert!(field(0, "stack_entries").is_some());
+        assert_eq!(field(1, "resource_type").as_deref(), Some("t"));
+        assert!(field(1, "debug_detail").is_none());
+        assert!(field(2, "stack_entries").is_none());
+    }
+
+    #[test]
+    fn classifiers_split_client_server_and_retryable() {
+        let svc = CanonicalError::service_unavailable(RetryInfo::after_seconds(1));
+        assert!(svc.is_server_error() && !svc.is_client_error() && svc.is_retryable());
+
+        let aborted = CanonicalError::aborted(ErrorInfo::new("CONFLICT", "svc"));
+        assert!(aborted.is_client_error() && aborted.is_retryable());
+
+        let not_found = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert!(not_found.is_client_error() && !not_found.is_retryable());
+
+        let cancelled = CanonicalError::cancelled(RequestInfo::new("r"));
+        assert!(cancelled.is_client_error());
+
+        let internal = CanonicalError::internal(DebugInfo::new("d"));
+        assert!(internal.is_server_error() && !internal.is_retryable());
+
+        let upstream_4xx = CanonicalError::unknown_with_status("teapot", 418);
+        assert!(upstream_4xx.is_client_error() && !upstream_4xx.is_server_error());
+    }
+
+    #[test]
+    fn status_code_policy_overrides_selected_categories() {
+        let policy = StatusCodePolicy::new()
+            .with_override(Category::FailedPrecondition, 412)
+            .with_override(Category::OutOfRange, 416);
+
+        let precondition = CanonicalError::failed_precondition(PreconditionFailure::new(vec![]));
+        let problem = Problem::from_error_with_policy(precondition, &policy);
+        assert_eq!(problem.status, 412);
+
+        let range = CanonicalError::out_of_range(Validation::constraint("page > last"));
+        assert_eq!(Problem::from_error_with_policy(range, &policy).status, 416);
+
+        let bad = CanonicalError::invalid_argument(Validation::format("bad json"));
+        assert_eq!(Problem::from_error_with_policy(bad, &policy).status, 400);
+    }
+
+    #[test]
+    fn default_status_code_policy_matches_builtin_mapping() {
+        let policy = StatusCodePolicy::default();
+        let errors = [
+            CanonicalError::unknown_with_status("upstream", 502),
+            CanonicalError::failed_precondition(PreconditionFailure::new(vec![])),
+            CanonicalError::cancelled(RequestInfo::new("r")),
+        ];
+        for err in errors {
+            let with_policy = serde_json::to_value(Problem::from_error_with_policy(err.clone(), &policy)).unwrap();
+            let builtin = serde_json::to_value(Problem::from_error(err)).unwrap();
+            assert_eq!(with_policy, builtin);
+        }
+    }
+
+    struct FrenchMessages;
+
+    impl MessageProvider for FrenchMessages {
+        fn default_message(&self, category: Category, ctx: &ErrorContext<'_>) -> Option<String> {
+            match (category, ctx) {
+                (Category::NotFound, ErrorContext::ResourceInfo(info)) => {
+                    Some(format!("Ressource introuvable : {}", info.resource_name))
+                }
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn message_provider_fills_default_message() {
+        let err = CanonicalError::not_found(ResourceInfo::new("t", "u-1")).with_message_from(&FrenchMessages);
+        assert_eq!(err.message(), "Ressource introuvable : u-1");
+    }
+
+    #[test]
+    fn message_provider_none_keeps_english_default() {
+        let err = CanonicalError::internal(DebugInfo::new("d")).with_message_from(&FrenchMessages);
+        assert_eq!(err.message(), CanonicalError::internal(DebugInfo::new("d")).message());
+        assert_eq!(
+            CanonicalError::not_found(ResourceInfo::new("t", "n")).message(),
+            "Resource not found"
+        );
+    }
+
+    #[test]
+    fn problem_response_parts() {
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")));
+        let (status, content_type, body) = problem.to_response_parts();
+        assert_eq!(status, 404);
+        assert_eq!(content_type, "application/problem+json");
+        assert_eq!(Problem::content_type(), content_type);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["status"], 404);
+    }
+
+    #[test]
+    fn from_error_debug_with_redacts_detail_and_stack() {
+        let err = CanonicalError::internal(DebugInfo::new("d")).with_debug_info(
+            DebugInfo::new("connect failed: postgres://admin:hunter2@db")
+                .with_stack(vec!["retry with hunter2".into(), "clean frame".into()]),
+        );
+        let redactor = |text: &str| text.replace("hunter2", "***");
+
+        let problem = Problem::from_error_debug_with(err.clone(), &redactor);
+        let debug = problem.debug.unwrap();
+        assert_eq!(debug["detail"], "connect failed: postgres://admin:***@db");
+        assert_eq!(debug["stack_entries"], serde_json::json!(["retry with ***", "clean frame"]));
+
+        assert!(Problem::from_error(err).debug.is_none());
+    }
+
+    #[cfg(feature = "regex-redactor")]
+    #[test]
+    fn regex_redactor_masks_password_pairs() {
+        let redactor = RegexRedactor::default();
+        assert_eq!(
+            redactor.redact("host=db;password=s3cr3t;user=app token=abc"),
+            "host=db;password=***;user=app token=***"
+        );
+    }
+
+    #[test]
+    fn add_debug_info_serializes_multiple_entries_as_array() {
+        let err = CanonicalError::internal(DebugInfo::new("d"))
+            .add_debug_info(DebugInfo::new("db: deadlock"))
+            .add_debug_info(DebugInfo::new("cache: miss"))
+            .add_debug_info(DebugInfo::new("handler: gave up"));
+        assert_eq!(err.debug_infos().len(), 3);
+        assert_eq!(err.debug_info().unwrap().detail, "db: deadlock");
+
+        let debug = Problem::from_error_debug(err).debug.unwrap();
+        let details: Vec<&str> = debug
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["detail"].as_str().unwrap())
+            .collect();
+        assert_eq!(details, vec!["db: deadlock", "cache: miss", "handler: gave up"]);
+    }
+
+    #[test]
+    fn single_debug_info_stays_an_object() {
+        let err = CanonicalError::internal(DebugInfo::new("d"))
+            .add_debug_info(DebugInfo::new("first"))
+            .with_debug_info(DebugInfo::new("replaced"));
+        assert_eq!(err.debug_infos().len(), 1);
+
+        let debug = Problem::from_error_debug(err).debug.unwrap();
+        assert_eq!(debug["detail"], "replaced");
+    }
+
+    #[test]
+    fn serde_json_syntax_error_maps_to_format() {
+        let parse_err = serde_json::from_str::<serde_json::Value>("{\n  \"email\": }").unwrap_err();
+        let err = CanonicalError::from(parse_err);
+        assert_eq!(err.category(), Category::InvalidArgument);
+        let CanonicalError::InvalidArgument { ctx: Validation::Format { format }, .. } = &err else {
+            panic!("expected a format violation, got {err:?}");
+        };
+        assert!(format.contains("line 2"), "{format}");
+        assert_eq!(err.debug_info().unwrap().detail, "line 2, column 12");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn serde_json_missing_field_maps_to_field_violation() {
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Signup {
+            email: String,
+        }
+
+        let parse_err = serde_json::from_str::<Signup>("{}").unwrap_err();
+        let err = CanonicalError::from(parse_err);
+        let CanonicalError::InvalidArgument {
+            ctx: Validation::FieldViolations { field_violations },
+            ..
+        } = err
+        else {
+            panic!("expected field violations");
+        };
+        assert_eq!(field_violations.len(), 1);
+        assert_eq!(field_violations[0].field, "email");
+        assert_eq!(field_violations[0].reason, "REQUIRED");
+    }
+
+    #[test]
+    fn derive_validate_collects_field_violations() {
+        #[derive(Validate)]
+        struct Signup {
+            #[validate(required, email)]
+            email: String,
+            #[validate(range(min = 18, max = 130))]
+            age: u8,
+            #[validate(required)]
+            name: Option<String>,
+            #[allow(dead_code)]
+            note: String,
+        }
+
+        let bad = Signup { email: "not-an-email".into(), age: 12, name: None, note: String::new() };
+        let err = bad.validate().unwrap_err();
+        let CanonicalError::InvalidArgument {
+            ctx: Validation::FieldViolations { field_violations },
+            ..
+        } = err
+        else {
+            panic!("expected field violations");
+        };
+        let summary: Vec<(&str, &str, &str)> = field_violations
+            .iter()
+            .map(|v| (v.field.as_str(), v.reason.as_str(), v.description.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("email", "INVALID_FORMAT", "must be a valid email address"),
+                ("age", "OUT_OF_RANGE", "must be between 18 and 130"),
+                ("name", "REQUIRED", "is required"),
+            ]
+        );
+
+        let good = Signup { email: "a@example.com".into(), age: 30, name: Some("Ada".into()), note: String::new() };
+        assert!(good.validate().is_ok());
+
+        let empty = Signup { email: String::new(), ..good };
+        let problem = Problem::from(empty.validate().unwrap_err());
+        let violations = problem.context["field_violations"].as_array().unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0]["reason"], "REQUIRED");
+    }
+
+    #[test]
+    fn derive_validate_skips_absent_optional_values() {
+        #[derive(Validate)]
+        struct Filter {
+            #[validate(email)]
+            contact: Option<String>,
+            #[validate(range(max = 100))]
+            limit: Option<u32>,
+        }
+
+        assert!(Filter { contact: None, limit: None }.validate().is_ok());
+        let err = Filter { contact: None, limit: Some(500) }.validate().unwrap_err();
+        assert_eq!(Problem::from(err).context["field_violations"][0]["description"], "must be at most 100");
+    }
+
+    #[test]
+    fn resource_error_message_overrides() {
+        #[resource_error(
+            "gts.cf.core.users.user.v1",
+            not_found = "User not found",
+            already_exists = "User already exists",
+        )]
+        struct UserResourceError;
+
+        assert_eq!(UserResourceError::not_found("u-1").message(), "User not found");
+        assert_eq!(UserResourceError::already_exists("u-1").message(), "User already exists");
+        assert_eq!(UserResourceError::data_loss("u-1").message(), "Data loss detected");
+        assert_eq!(
+            UserResourceError::not_found("u-1").resource_type(),
+            Some("gts.cf.core.users.user.v1")
+        );
+    }
+
+    #[test]
+    fn resource_error_typed_id_parameter() {
+        struct UserId(u64);
+
+        impl fmt::Display for UserId {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "user-{}", self.0)
+            }
+        }
+
+        #[resource_error("gts.cf.core.users.user.v1", id = UserId, not_found = "User not found")]
+        struct UserResourceError;
+
+        let err = UserResourceError::not_found(UserId(7));
+        assert_eq!(err.message(), "User not found");
+        let CanonicalError::NotFound { ctx, .. } = &err else {
+            panic!("expected NotFound");
+        };
+        assert_eq!(ctx.resource_name, "user-7");
+        assert!(matches!(UserResourceError::data_loss(UserId(8)), CanonicalError::DataLoss { .. }));
+    }
+
+    #[test]
+    fn resource_error_injects_resource_name_into_failures() {
+        #[resource_error("gts.cf.core.users.user.v1", inject_resource_name = true)]
+        struct UserResourceError;
+
+        let err = UserResourceError::failed_precondition(
+            "u-1",
+            PreconditionFailure::new(vec![PreconditionViolation::new("STATE", "u-1", "user is suspended")]),
+        );
+        let CanonicalError::FailedPrecondition { ctx, .. } = &err else {
+            panic!("expected FailedPrecondition");
+        };
+        assert_eq!(ctx.violations.len(), 2);
+        assert_eq!(
+            ctx.violations[0],
+            PreconditionViolation::new("RESOURCE", "u-1", "gts.cf.core.users.user.v1")
+        );
+
+        let err = UserResourceError::resource_exhausted("u-1", QuotaFailure::new(vec![]));
+        let CanonicalError::ResourceExhausted { ctx, .. } = &err else {
+            panic!("expected ResourceExhausted");
+        };
+        assert_eq!(ctx.violations, vec![QuotaViolation::new("u-1", "gts.cf.core.users.user.v1")]);
+    }
+
+    /// Drives an in-memory extractor future, which never actually waits.
+    #[cfg(feature = "axum")]
+    fn poll_ready<F: std::future::Future>(fut: F) -> F::Output {
+        let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+        match std::pin::pin!(fut).poll(&mut cx) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("in-memory extractor should not wait"),
+        }
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn json_rejection_maps_to_invalid_argument() {
+        use axum::extract::{FromRequest, Json};
+        use axum::http::Request;
+
+        #[derive(Debug, Deserialize)]
+        #[allow(dead_code)]
+        struct Body {
+            name: String,
+        }
+
+        let req = Request::builder()
+            .header("content-type", "application/json")
+            .body(axum::body::Body::from("{\"name\": 1}"))
+            .unwrap();
+        let rejection = poll_ready(Json::<Body>::from_request(req, &())).unwrap_err();
+        let err = CanonicalError::from(rejection);
+        let CanonicalError::InvalidArgument { ctx: Validation::Format { format }, .. } = &err else {
+            panic!("expected InvalidArgument with a format violation, got {err:?}");
+        };
+        assert!(format.contains("name"), "{format}");
+
+        let req = Request::builder().body(axum::body::Body::from("{}")).unwrap();
+        let rejection = poll_ready(Json::<Body>::from_request(req, &())).unwrap_err();
+        let err = CanonicalError::from(rejection);
+        assert!(matches!(
+            err,
+            CanonicalError::InvalidArgument { ctx: Validation::Constraint { .. }, .. }
+        ));
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn reject_as_uses_rejection_status() {
+        use axum::extract::rejection::MissingPathParams;
+
+        let err = reject_as(MissingPathParams::default());
+        assert_eq!(err.category(), Category::Internal);
+    }
+
+    #[test]
+    fn most_severe_picks_highest_rank() {
+        let errors = vec![
+            CanonicalError::cancelled(RequestInfo::new("req-1")),
+            CanonicalError::not_found(ResourceInfo::new("user", "u-1")),
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(5)).with_message("first"),
+            CanonicalError::unknown_with_status("upstream", 502),
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(5)).with_message("second"),
+        ];
+        let worst = most_severe(&errors).unwrap();
+        assert_eq!(worst.severity_rank(), 2);
+        assert_eq!(worst.message(), "first");
+
+        let mut errors = errors;
+        errors.push(CanonicalError::data_loss(ResourceInfo::new("blob", "b-1")));
+        assert_eq!(most_severe(&errors).unwrap().category(), Category::DataLoss);
+
+        assert_eq!(errors[0].severity_rank(), 0);
+        assert_eq!(errors[1].severity_rank(), 1);
+        assert!(most_severe(&[]).is_none());
+    }
+
+    #[test]
+    fn resource_constructors_set_context_and_resource_type() {
+        let err = CanonicalError::not_found_resource("gts.cf.core.users.user.v1", "u-1");
+        assert_eq!(
+            err,
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "u-1"))
+                .with_resource_type("gts.cf.core.users.user.v1")
+        );
+
+        let err = CanonicalError::already_exists_resource("gts.cf.core.users.user.v1", "u-1");
+        assert_eq!(err.category(), Category::AlreadyExists);
+        assert_eq!(err.resource_type(), Some("gts.cf.core.users.user.v1"));
+
+        let err = CanonicalError::data_loss_resource("gts.cf.core.files.blob.v1", "b-1");
+        let CanonicalError::DataLoss { ctx, .. } = &err else {
+            panic!("expected DataLoss");
+        };
+        assert_eq!(ctx.resource_name, "b-1");
+        assert_eq!(err.resource_type(), Some("gts.cf.core.files.blob.v1"));
+    }
+
+    #[test]
+    fn push_stack_entry_accumulates_frames() {
+        let mut err = CanonicalError::unknown("boom")
+            .with_debug_info(DebugInfo::new("boom").with_stack(vec!["db::query".to_string()]));
+        err.push_stack_entry("repo::load");
+        err.push_stack_entry("handler::get");
+        assert_eq!(err.debug_info().unwrap().detail, "boom");
+        assert_eq!(err.debug_info().unwrap().stack_entries, vec!["db::query", "repo::load", "handler::get"]);
+
+        let mut err = CanonicalError::not_found(ResourceInfo::new("user", "u-1"));
+        err.push_stack_entry("handler::get");
+        assert_eq!(err.debug_infos(), [DebugInfo::new("").with_stack(vec!["handler::get".to_string()])]);
+    }
+
+    #[test]
+    fn deadline_exceeded_after_records_elapsed_ms() {
+        let err = CanonicalError::deadline_exceeded_after(
+            RequestInfo::new("req-1"),
+            std::time::Duration::from_millis(1500),
+        );
+        assert_eq!(err.message(), "Operation did not complete within the allowed time");
+        let problem = Problem::from_error(err.clone());
+        assert_eq!(problem.context, serde_json::json!({ "request_id": "req-1", "elapsed_ms": 1500 }));
+        assert_eq!(CanonicalError::try_from(problem).unwrap(), err);
+
+        let problem = Problem::from_error(CanonicalError::deadline_exceeded(RequestInfo::new("req-1")));
+        assert_eq!(problem.context, serde_json::json!({ "request_id": "req-1" }));
+    }
+
+    #[test]
+    fn to_human_string_golden() {
+        let err = CanonicalError::invalid_argument(Validation::fields(vec![FieldViolation::new(
+            "email",
+            "must be a valid email address",
+            "INVALID_FORMAT",
+        )]))
+        .with_resource_type("gts.cf.core.users.user.v1");
+        assert_eq!(
+            err.to_human_string(),
+            "400 Invalid Argument: Request validation failed [gts.cf.core.users.user.v1] \
+             (email: must be a valid email address)"
+        );
+
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(30));
+        assert_eq!(
+            err.to_human_string(),
+            "503 Unavailable: Service temporarily unavailable (retry after 30s)"
+        );
+
+        let err = CanonicalError::not_found_resource("gts.cf.core.users.user.v1", "u-1");
+        assert_eq!(
+            err.to_human_string(),
+            "404 Not Found: Resource not found [gts.cf.core.users.user.v1] (resource_name: u-1)"
+        );
+
+        let err = CanonicalError::internal(DebugInfo::new("db down"));
+        assert_eq!(
+            err.to_human_string(),
+            "500 Internal: An internal error occurred. Please retry later."
+        );
+        assert_eq!(err.to_string(), "internal: An internal error occurred. Please retry later.");
+    }
+
+    #[test]
+    fn alternate_display_pretty_prints_context() {
+        let err = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "u-1"));
+        assert_eq!(format!("{err}"), "not_found: Resource not found");
+        assert_eq!(
+            format!("{err:#}"),
+            "not_found: Resource not found\n{\n  \"description\": \"Resource not found\",\n  \
+             \"resource_name\": \"u-1\",\n  \"resource_type\": \"gts.cf.core.users.user.v1\"\n}"
+        );
+    }
+
+    #[test]
+    fn from_error_with_context_fills_instance_and_trace_id() {
+        let ctx = ProblemContext::new("GET", "/users/u-1?expand=roles").with_trace_id("trace-abc");
+        let problem = Problem::from_error_with_context(CanonicalError::not_found_resource("user", "u-1"), &ctx);
+        assert_eq!(problem.instance.as_deref(), Some("/users/u-1?expand=roles"));
+        assert_eq!(problem.trace_id.as_deref(), Some("trace-abc"));
+
+        let ctx = ProblemContext::new("GET", "/users/u-1").with_trace_id("");
+        let problem = Problem::from_error_with_context(CanonicalError::not_found_resource("user", "u-1"), &ctx);
+        assert_eq!(problem.instance.as_deref(), Some("/users/u-1"));
+        assert_eq!(problem.trace_id, None);
+    }
+
+    #[test]
+    fn boxed_error_converts_to_internal_with_cause_chain() {
+        #[derive(Debug)]
+        struct Outer(std::io::Error);
+
+        impl fmt::Display for Outer {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("loading config failed")
+            }
+        }
+
+        impl std::error::Error for Outer {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        fn load() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+            Err(Box::new(Outer(std::io::Error::other("disk unplugged"))))
+        }
+
+        #[allow(clippy::result_large_err)]
+        fn handler() -> Result<(), CanonicalError> {
+            load()?;
+            Ok(())
+        }
+
+        let err = handler().unwrap_err();
+        let CanonicalError::Internal { ctx, .. } = &err else {
+            panic!("expected Internal");
+        };
+        assert_eq!(ctx.detail, "loading config failed");
+        assert_eq!(ctx.stack_entries, vec!["caused by: disk unplugged"]);
+        assert_eq!(
+            std::error::Error::source(&err).map(|e| e.to_string()).as_deref(),
+            Some("loading config failed")
+        );
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn anyhow_error_maps_to_internal_with_chain() {
+        let err = anyhow::anyhow!("disk unplugged").context("loading config failed");
+        let err = CanonicalError::from(err);
+        let CanonicalError::Internal { ctx, .. } = &err else {
+            panic!("expected Internal");
+        };
+        assert_eq!(ctx.detail, "loading config failed");
+        assert_eq!(ctx.stack_entries[0], "caused by: disk unplugged");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[cfg(feature = "anyhow")]
+    #[test]
+    fn anyhow_round_trip_does_not_double_wrap() {
+        let original = CanonicalError::not_found_resource("user", "u-1");
+        let back = CanonicalError::from(original.clone().context_anyhow());
+        assert_eq!(back, original);
+    }
+
+    #[test]
+    fn from_display_builds_each_category() {
+        for category in Category::ALL {
+            let err = CanonicalError::from_display(category, "connection reset");
+            assert_eq!(err.category(), category);
+            assert_eq!(err.message(), category.default_message());
+            assert_eq!(err.debug_info().map(|info| info.detail.as_str()), Some("connection reset"));
+            if let ErrorContext::ResourceInfo(ctx) = err.context() {
+                assert_eq!(ctx.description, category.default_message());
+            }
+            let public = serde_json::to_string(&Problem::from_error(err)).unwrap();
+            assert!(!public.contains("connection reset"), "{category:?}: {public}");
+        }
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[test]
+    fn sqlx_errors_map_to_categories() {
+        assert_eq!(CanonicalError::from(sqlx::Error::RowNotFound).category(), Category::NotFound);
+        assert_eq!(CanonicalError::from(sqlx::Error::PoolTimedOut).category(), Category::DeadlineExceeded);
+
+        #[derive(Debug)]
+        struct UniqueViolation;
+
+        impl fmt::Display for UniqueViolation {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("duplicate key value violates unique constraint \"users_email_key\"")
+            }
+        }
+
+        impl std::error::Error for UniqueViolation {}
+
+        impl sqlx::error::DatabaseError for UniqueViolation {
+            fn message(&self) -> &str {
+                "duplicate key value violates unique constraint \"users_email_key\""
+            }
+
+            fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+                Some("23505".into())
+            }
+
+            fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+                self
+            }
+
+            fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+                self
+            }
+
+            fn kind(&self) -> sqlx::error::ErrorKind {
+                sqlx::error::ErrorKind::UniqueViolation
+            }
+        }
+
+        let err = CanonicalError::from(sqlx::Error::Database(Box::new(UniqueViolation)))
+            .with_resource_type("gts.cf.core.users.user.v1");
+        assert_eq!(err.category(), Category::AlreadyExists);
+        assert_eq!(err.message(), "Resource already exists");
+        assert_eq!(
+            err.debug_info().map(|info| info.detail.as_str()),
+            Some("duplicate key value violates unique constraint \"users_email_key\"")
+        );
+        let problem = Problem::from_error(err);
+        assert!(!problem.detail.contains("users_email_key"), "{}", problem.detail);
+        assert!(!problem.context.to_string().contains("users_email_key"));
+
+        let err = CanonicalError::from(sqlx::Error::Protocol("unexpected packet".to_string()));
+        let CanonicalError::Internal { ctx, .. } = &err else {
+            panic!("expected Internal");
+        };
+        assert!(ctx.detail.contains("unexpected packet"), "{}", ctx.detail);
+    }
+
+    #[test]
+    fn problem_head_matches_full_problem() {
+        for err in [
+            CanonicalError::not_found_resource("gts.cf.core.users.user.v1", "u-1"),
+            CanonicalError::unknown_with_status("teapot", 418),
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(5)),
+        ] {
+            let head = err.problem_head();
+            let problem = Problem::from_error(err);
+            assert_eq!(head.status, problem.status);
+            assert_eq!(head.title, problem.title);
+            assert_eq!(head.problem_type, problem.problem_type);
+        }
+    }
+
+    #[test]
+    fn from_error_ref_matches_from_error() {
+        let err = CanonicalError::not_found_resource("gts.cf.core.users.user.v1", "u-1")
+            .with_debug_info(DebugInfo::new("cache miss"));
+        let borrowed = Problem::from_error_ref(&err);
+        assert_eq!(
+            serde_json::to_value(&borrowed).unwrap(),
+            serde_json::to_value(Problem::from_error(err.clone())).unwrap()
+        );
+        assert!(borrowed.debug.is_none());
+        assert_eq!(err.message(), "Resource not found");
+    }
+
+    #[test]
+    fn from_error_borrows_static_type_and_title() {
+        let problem = Problem::from_error(CanonicalError::not_found_resource("user", "u-1"));
+        assert!(matches!(problem.problem_type, Cow::Borrowed(_)));
+        assert!(matches!(problem.title, Cow::Borrowed("Not Found")));
+
+        let json = serde_json::to_string(&problem).unwrap();
+        let parsed: Problem = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.problem_type, problem.problem_type);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn category_from_name_inverts_name() {
+        for category in Category::ALL {
+            assert_eq!(Category::from_name(category.name()), Some(category));
+        }
+        assert_eq!(Category::from_name("not_found"), Some(Category::NotFound));
+        assert_eq!(Category::from_name("NotFound"), None);
+        assert_eq!(Category::from_name(""), None);
+    }
+
+    #[test]
+    fn roundtrip_preserves_instance_and_trace_id() {
+        let problem = Problem::from_error(CanonicalError::not_found_resource("user", "u-1"))
+            .with_instance("/users/u-1")
+            .with_trace_id("trace-abc");
+        let err = CanonicalError::try_from(problem).unwrap();
+        assert_eq!(err.instance(), Some("/users/u-1"));
+        assert_eq!(err.trace_id(), Some("trace-abc"));
+
+        let reemitted = Problem::from_error(err);
+        assert_eq!(reemitted.instance.as_deref(), Some("/users/u-1"));
+        assert_eq!(reemitted.trace_id.as_deref(), Some("trace-abc"));
+
+        let err = CanonicalError::unknown("boom").with_trace_id("trace-def");
+        let parts = err.clone().into_parts();
+        assert_eq!(parts.meta.trace_id.as_deref(), Some("trace-def"));
+        assert_eq!(CanonicalError::from_parts(parts).unwrap(), err);
+    }
+
+    #[test]
+    fn problem_validate_checks_category_and_status() {
+        let mut problem = Problem::from_error(CanonicalError::not_found_resource("user", "u-1"));
+        assert!(problem.validate().is_ok());
+
+        problem.status = 500;
+        assert!(matches!(
+            problem.validate(),
+            Err(ProblemConversionError::StatusMismatch { ref category, expected: 404, actual: 500 })
+                if category == "not_found"
+        ));
+
+        problem.problem_type = "gts.cf.core.errors.err.v1~cf.core.errors.nonexistent.v1~".into();
+        assert!(matches!(problem.validate(), Err(ProblemConversionError::UnknownCategory(_))));
+
+        assert!(Problem::from_error(CanonicalError::unknown_with_status("teapot", 418)).validate().is_ok());
+    }
+
+    #[test]
+    fn problem_validate_checks_code_against_type() {
+        let problem = Problem::from_error(CanonicalError::not_found_resource("user", "u-1"))
+            .with_type_url("https://errors.example.com");
+        assert!(problem.validate().is_ok());
+        let mut without_code = problem.clone();
+        without_code.code = None;
+        assert!(without_code.validate().is_ok());
+        assert!(CanonicalError::try_from(without_code).is_ok());
+
+        let mut legacy = Problem::from_error(CanonicalError::service_unavailable(RetryInfo::after_seconds(5)));
+        legacy.code = Some("unavailable".to_string());
+        assert!(legacy.validate().is_ok());
+        assert!(CanonicalError::try_from(legacy).is_ok());
+
+        let mut mismatched = problem;
+        mismatched.code = Some("aborted".to_string());
+        assert!(matches!(
+            mismatched.validate(),
+            Err(ProblemConversionError::CategoryMismatch { ref code, ref category })
+                if code == "aborted" && category == "not_found"
+        ));
+
+        let mut untyped = Problem::from_error(CanonicalError::not_found_resource("user", "u-1"));
+        untyped.problem_type = "not-a-gts-uri".into();
+        assert_eq!(untyped.code.as_deref(), Some("not_found"));
+        assert!(matches!(untyped.validate(), Err(ProblemConversionError::InvalidType(_))));
+    }
+
+    #[test]
+    fn try_from_error_matches_from_error() {
+        let err = CanonicalError::invalid_field("email", "must be a valid email address", "INVALID_FORMAT");
+        let problem = Problem::try_from_error(err.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&problem).unwrap(),
+            serde_json::to_value(Problem::from_error(err)).unwrap()
+        );
+    }
+
+    #[test]
+    fn precondition_violation_subject_path_serializes_as_array() {
+        let violation = PreconditionViolation::new("STATE", "tenant.users", "tenant is over its user limit")
+            .with_subject_path(["tenant", "users"]);
+        assert_eq!(
+            serde_json::to_value(&violation).unwrap(),
+            serde_json::json!({
+                "type": "STATE",
+                "subject": "tenant.users",
+                "description": "tenant is over its user limit",
+                "subject_path": ["tenant", "users"]
+            })
+        );
+        let parsed: PreconditionViolation = serde_json::from_value(serde_json::to_value(&violation).unwrap()).unwrap();
+        assert_eq!(parsed, violation);
+
+        let flat = PreconditionViolation::new("STATE", "tenant", "suspended");
+        assert!(serde_json::to_value(&flat).unwrap().get("subject_path").is_none());
+    }
+
+    #[test]
+    fn violation_counts_across_failure_types() {
+        let validation = Validation::fields(vec![
+            FieldViolation::new("email", "required", "REQUIRED"),
+            FieldViolation::new("name", "too long", "TOO_LONG"),
+        ]);
+        assert_eq!(validation.violation_count(), 2);
+        assert_eq!(Validation::format("bad json").violation_count(), 1);
+
+        let quota = QuotaFailure::new(vec![QuotaViolation::new("requests", "limit reached")]);
+        assert_eq!(quota.len(), 1);
+        assert_eq!(quota.iter().map(|v| v.subject.as_str()).collect::<Vec<_>>(), ["requests"]);
+
+        let precondition = PreconditionFailure::new(vec![]);
+        assert!(precondition.is_empty());
+        assert_eq!(precondition.iter().count(), 0);
+
+        assert_eq!(CanonicalError::invalid_argument(validation).violation_count(), Some(2));
+        assert_eq!(CanonicalError::resource_exhausted(quota).violation_count(), Some(1));
+        assert_eq!(CanonicalError::failed_precondition(precondition).violation_count(), Some(0));
+        assert_eq!(CanonicalError::not_found_resource("user", "u-1").violation_count(), None);
+    }
+
+    #[cfg(feature = "validator")]
+    #[test]
+    fn validator_errors_flatten_into_field_violations() {
+        use validator::{ValidationError, ValidationErrors};
+
+        let mut address = ValidationErrors::new();
+        address.add("city", ValidationError::new("length"));
+        let mut item = ValidationErrors::new();
+        item.add("name", ValidationError::new("required"));
+
+        let mut errors = ValidationErrors::new();
+        errors.add("email", ValidationError::new("email").with_message("not an email".into()));
+        errors.add("code", ValidationError::new("not_reserved"));
+        errors.errors_mut().insert(
+            "address".into(),
+            validator::ValidationErrorsKind::Struct(Box::new(address)),
+        );
+        errors.errors_mut().insert(
+            "items".into(),
+            validator::ValidationErrorsKind::List([(0, Box::new(item))].into_iter().collect()),
+        );
+
+        let err = CanonicalError::from(errors);
+        let CanonicalError::InvalidArgument { ctx: Validation::FieldViolations { field_violations }, .. } = &err
+        else {
+            panic!("expected field violations, got {err:?}");
+        };
+        assert_eq!(
+            field_violations,
+            &vec![
+                FieldViolation::new("address.city", "failed `length` validation", "OUT_OF_RANGE"),
+                FieldViolation::new("code", "failed `not_reserved` validation", "NOT_RESERVED"),
+                FieldViolation::new("email", "not an email", "INVALID_FORMAT"),
+                FieldViolation::new("items[0].name", "failed `required` validation", "REQUIRED"),
+            ]
+        );
+    }
+
+    #[test]
+    fn debug_info_from_panic_payloads() {
+        let payload = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(DebugInfo::from_panic(&*payload).detail, "panic: static message");
+
+        let id = 7;
+        let payload = std::panic::catch_unwind(|| panic!("task {id} crashed")).unwrap_err();
+        assert_eq!(DebugInfo::from_panic(&*payload).detail, "panic: task 7 crashed");
+
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(42_u32)).unwrap_err();
+        let err = CanonicalError::internal_from_panic(&*payload);
+        assert!(err.is_internal());
+        assert_eq!(err.debug_info().unwrap().detail, "panic: non-string panic payload");
+
+        let payload = std::panic::catch_unwind(|| panic!("db password is hunter2")).unwrap_err();
+        let err = CanonicalError::internal_from_panic(&*payload);
+        let public = serde_json::to_string(&Problem::from_error(err.clone())).unwrap();
+        assert!(!public.contains("hunter2"), "{public}");
+        let debug = serde_json::to_string(&Problem::from_error_debug(err)).unwrap();
+        assert!(debug.contains("hunter2"));
+    }
+
+    #[test]
+    fn as_accessors_return_context_for_matching_variant_only() {
+        let quota = QuotaFailure::new(vec![QuotaViolation::new("requests", "limit reached")]);
+        let err = CanonicalError::resource_exhausted(quota.clone());
+        assert_eq!(err.as_resource_exhausted(), Some(&quota));
+        assert_eq!(err.as_failed_precondition(), None);
+        assert_eq!(err.as_not_found(), None);
+
+        let err = CanonicalError::not_found_resource("user", "u-1");
+        assert_eq!(err.as_not_found().map(|ctx| ctx.resource_name.as_str()), Some("u-1"));
+        assert_eq!(err.as_already_exists(), None);
+        assert_eq!(err.as_data_loss(), None);
+
+        let err = CanonicalError::out_of_range(Validation::constraint("page must be <= 100"));
+        assert_eq!(err.as_out_of_range(), Some(&Validation::constraint("page must be <= 100")));
+        assert_eq!(err.as_invalid_argument(), None);
+
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(5));
+        assert_eq!(err.as_service_unavailable().and_then(|ctx| ctx.retry_after_seconds), Some(5));
+        assert_eq!(err.as_internal(), None);
+    }
+
+    #[test]
+    fn is_predicates_match_only_their_category() {
+        type Predicate = fn(&CanonicalError) -> bool;
+        let predicates: [(Category, Predicate); 16] = [
+            (Category::Cancelled, CanonicalError::is_cancelled),
+            (Category::Unknown, CanonicalError::is_unknown),
+            (Category::InvalidArgument, CanonicalError::is_invalid_argument),
+            (Category::DeadlineExceeded, CanonicalError::is_deadline_exceeded),
+            (Category::NotFound, CanonicalError::is_not_found),
+            (Category::AlreadyExists, CanonicalError::is_already_exists),
+            (Category::PermissionDenied, CanonicalError::is_permission_denied),
+            (Category::ResourceExhausted, CanonicalError::is_resource_exhausted),
+            (Category::FailedPrecondition, CanonicalError::is_failed_precondition),
+            (Category::Aborted, CanonicalError::is_aborted),
+            (Category::OutOfRange, CanonicalError::is_out_of_range),
+            (Category::Unimplemented, CanonicalError::is_unimplemented),
+            (Category::Internal, CanonicalError::is_internal),
+            (Category::ServiceUnavailable, CanonicalError::is_service_unavailable),
+            (Category::DataLoss, CanonicalError::is_data_loss),
+            (Category::Unauthenticated, CanonicalError::is_unauthenticated),
+        ];
+        for category in Category::ALL {
+            let err = CanonicalError::from_display(category, "boom");
+            for (expected, predicate) in &predicates {
+                assert_eq!(predicate(&err), *expected == category, "{expected:?} predicate on {category:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn try_from_problem_accepts_debug_object_or_array() {
+        let err = CanonicalError::unknown("boom").with_debug_info(DebugInfo::new("first"));
+        let problem = Problem::from_error_debug(err);
+        assert!(problem.debug.as_ref().unwrap().is_object());
+        let parsed = CanonicalError::try_from(problem).unwrap();
+        assert_eq!(parsed.debug_infos(), [DebugInfo::new("first")]);
+
+        let err = CanonicalError::unknown("boom")
+            .add_debug_info(DebugInfo::new("first"))
+            .add_debug_info(DebugInfo::new("second").with_stack(vec!["frame".to_string()]));
+        let problem = Problem::from_error_debug(err.clone());
+        assert!(problem.debug.as_ref().unwrap().is_array());
+        assert_eq!(CanonicalError::try_from(problem).unwrap().debug_infos(), err.debug_infos());
+
+        let mut problem = Problem::from_error(CanonicalError::unknown("boom"));
+        problem.debug = Some(serde_json::json!("not debug info"));
+        assert!(matches!(
+            CanonicalError::try_from(problem),
+            Err(ProblemConversionError::ContextDeserializationFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn safe_log_string_omits_debug_and_message() {
+        let err = CanonicalError::internal(DebugInfo::new("SELECT * FROM users WHERE password='hunter2'"))
+            .with_message("user bob@example.com failed")
+            .with_debug_info(DebugInfo::new("secret detail").with_stack(vec!["secret frame".to_string()]))
+            .with_resource_type("gts.cf.core.users.user.v1");
+        let line = err.to_safe_log_string();
+        assert_eq!(
+            line,
+            "internal: An internal error occurred. Please retry later. \
+             (status=500, title=Internal, resource_type=gts.cf.core.users.user.v1)"
+        );
+        for leaked in ["hunter2", "bob@example.com", "secret"] {
+            assert!(!line.contains(leaked), "{leaked} leaked into {line}");
+        }
+
+        let err = CanonicalError::already_exists(ResourceInfo::new("user", "bob@example.com"));
+        assert_eq!(
+            err.to_safe_log_string(),
+            "already_exists: Resource already exists (status=409, title=Already Exists)"
+        );
+    }
+
+    #[test]
+    fn category_default_message_matches_static_constructors() {
+        for category in Category::ALL {
+            let err = CanonicalError::with_empty_context(category);
+            assert_eq!(err.message(), category.default_message(), "{category:?}");
+        }
+        for err in sample_error_per_category() {
+            // These constructors take the message from the context instead.
+            let from_context = matches!(err.category(), Category::Unknown | Category::AlreadyExists | Category::DataLoss)
+                || matches!(err.context(), ErrorContext::Validation(_));
+            if !from_context {
+                assert_eq!(err.message(), err.category().default_message());
+            }
+        }
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn problem_xml_golden_not_found() {
+        let err = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "u-1"))
+            .with_message("User <u-1> & \"friends\" not found");
+        let xml = Problem::from_error(err).with_instance("/users/u-1").to_problem_xml();
+        assert_eq!(
+            xml,
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?><problem xmlns="urn:ietf:rfc:7807">"#,
+                "<type>gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~</type>",
+                "<title>Not Found</title>",
+                "<status>404</status>",
+                "<detail>User &lt;u-1&gt; &amp; &quot;friends&quot; not found</detail>",
+                "<instance>/users/u-1</instance>",
+                "<code>not_found</code>",
+                "<context>",
+                "<description>Resource not found</description>",
+                "<resource_name>u-1</resource_name>",
+                "<resource_type>gts.cf.core.users.user.v1</resource_type>",
+                "</context>",
+                "</problem>",
+            )
+        );
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn problem_xml_wraps_hostile_keys_and_drops_forbidden_chars() {
+        let err = CanonicalError::permission_denied(
+            ErrorInfo::new("DENIED", "auth").with_metadata("a><evil", "x").with_metadata("1st", "y\u{0}z"),
+        );
+        let xml = Problem::from_error(err).to_problem_xml();
+        assert!(!xml.contains("<evil"));
+        assert!(xml.contains(r#"<i name="a&gt;&lt;evil">x</i>"#));
+        assert!(xml.contains(r#"<i name="1st">yz</i>"#));
+        assert!(!xml.contains('\u{0}'));
+        assert!(xml.contains("<reason>DENIED</reason>"));
+    }
+
+    #[test]
+    fn to_flat_fields_flattens_error_info() {
+        let err = CanonicalError::permission_denied(
+            ErrorInfo::new("MISSING_ROLE", "auth.example.com").with_metadata("role", "admin"),
+        )
+        .with_resource_type("gts.cf.core.users.user.v1");
+        let fields: Vec<(String, String)> = err.to_flat_fields().into_iter().collect();
+        let expected = [
+            ("error.category", "permission_denied"),
+            ("error.domain", "auth.example.com"),
+            ("error.message", "You do not have permission to perform this operation"),
+            ("error.meta.role", "admin"),
+            ("error.reason", "MISSING_ROLE"),
+            ("error.resource_type", "gts.cf.core.users.user.v1"),
+            ("error.status", "403"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        assert_eq!(fields, expected);
+
+        let fields = CanonicalError::not_found(ResourceInfo::new("user", "u-1")).to_flat_fields();
+        assert_eq!(fields.keys().collect::<Vec<_>>(), ["error.category", "error.message", "error.status"]);
+    }
+
+    #[test]
+    fn internal_context_is_public_while_debug_info_is_debug_only() {
+        let err = CanonicalError::internal_with_message("Ledger unavailable", DebugInfo::new("public detail"))
+            .with_debug_info(DebugInfo::new("secret detail"));
+        assert_eq!(err.message(), "Ledger unavailable");
+
+        let debug = Problem::from_error_debug(err.clone());
+        assert_eq!(debug.context["detail"], "public detail");
+        assert_eq!(debug.debug.unwrap()["detail"], "secret detail");
+
+        let public = Problem::from_error(err);
+        assert_eq!(public.context["detail"], "public detail");
+        assert!(public.debug.is_none());
+    }
+
+    #[test]
+    fn parse_errors_convert_to_invalid_argument_format() {
+        let err: CanonicalError = "abc".parse::<i32>().unwrap_err().into();
+        assert_eq!(err.category(), Category::InvalidArgument);
+        assert!(matches!(err.as_invalid_argument(), Some(Validation::Format { format }) if format == "invalid digit found in string"));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err: CanonicalError = "abc".parse::<f64>().unwrap_err().into();
+        assert!(matches!(err.as_invalid_argument(), Some(Validation::Format { format }) if format == "invalid float literal"));
+    }
+
+    #[test]
+    fn resource_info_parent_serializes_recursively() {
+        let tenant = ResourceInfo::new("gts.cf.core.tenants.tenant.v1", "t-1");
+        let project = ResourceInfo::new("gts.cf.core.projects.project.v1", "p-1").with_parent(tenant);
+        let user = ResourceInfo::new("gts.cf.core.users.user.v1", "u-1").with_parent(project);
+        let json = serde_json::to_value(&user).unwrap();
+        assert_eq!(json["parent"]["resource_name"], "p-1");
+        assert_eq!(json["parent"]["description"], "");
+        assert_eq!(json["parent"]["parent"]["resource_name"], "t-1");
+        assert_eq!(json["description"], "Resource not found");
+
+        let archived = ResourceInfo::new("p", "p-1").with_description("Archived");
+        let described = ResourceInfo::new("t", "n").with_parent(archived);
+        assert_eq!(described.parent.unwrap().description, "Archived");
+        assert!(json["parent"]["parent"].get("parent").is_none());
+        let parsed: ResourceInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, user);
+
+        let flat = serde_json::to_value(ResourceInfo::new("t", "n")).unwrap();
+        assert!(flat.get("parent").is_none());
+
+        let deep = (0..32).fold(ResourceInfo::new("t", "root"), |parent, i| {
+            ResourceInfo::new("t", format!("n-{i}")).with_parent(parent)
+        });
+        let text = serde_json::to_string(&deep).unwrap();
+        assert_eq!(serde_json::from_str::<ResourceInfo>(&text).unwrap(), deep);
+
+        let components = openapi_components();
+        assert_eq!(
+            components["ResourceInfoV1"]["properties"]["parent"]["anyOf"][0]["$ref"],
+            "#/components/schemas/ResourceInfoV1"
+        );
+    }
+
+    #[test]
+    fn resource_info_uri_round_trip() {
+        let info = ResourceInfo::from_uri("gts://gts.cf.core.users.user.v1~/tenant-1/user-123").unwrap();
+        assert_eq!(info.resource_type, "gts.cf.core.users.user.v1");
+        assert_eq!(info.resource_name, "tenant-1/user-123");
+        assert_eq!(info.to_uri(), "gts://gts.cf.core.users.user.v1~/tenant-1/user-123");
+
+        assert!(matches!(
+            ResourceInfo::from_uri("https://example.com/user-1"),
+            Err(ResourceUriError::MissingScheme(_))
+        ));
+        assert!(matches!(
+            ResourceInfo::from_uri("gts://gts.cf.core.users.user.v1~"),
+            Err(ResourceUriError::MissingSeparator(_))
+        ));
+        assert!(matches!(
+            ResourceInfo::from_uri("gts://~/user-1"),
+            Err(ResourceUriError::EmptyComponent(_))
+        ));
+    }
+
+    #[test]
+    fn failure_types_collect_from_iterators() {
+        let limits = [("cpu", Some("over cpu quota")), ("disk", None)];
+        let quota: QuotaFailure = limits
+            .iter()
+            .filter_map(|(subject, desc)| desc.map(|d| QuotaViolation::new(*subject, d)))
+            .collect();
+        assert_eq!(quota.len(), 1);
+        assert_eq!(quota.violations[0].subject, "cpu");
+
+        let preconditions = PreconditionFailure::from_iter([PreconditionViolation::new("STATE", "vm-1", "running")]);
+        assert_eq!(preconditions.len(), 1);
+
+        let validation: Validation = ["name", "email"]
+            .into_iter()
+            .map(|field| FieldViolation::new(field, "required", "REQUIRED"))
+            .collect();
+        assert_eq!(validation.violation_count(), 2);
+        assert!(matches!(validation, Validation::FieldViolations { .. }));
+    }
+
+    #[test]
+    fn context_setters_replace_matching_payload_only() {
+        let mut err = CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "pending"))
+            .with_message("User lookup failed");
+        assert!(err.set_resource_info(ResourceInfo::new("gts.cf.core.users.user.v1", "u-42")));
+        assert_eq!(err.as_not_found().unwrap().resource_name, "u-42");
+        assert_eq!(err.message(), "User lookup failed");
+        assert!(!err.set_validation(Validation::format("x")));
+        assert!(!err.set_error_info(ErrorInfo::new("R", "D")));
+
+        let mut err = CanonicalError::internal(DebugInfo::new("placeholder"));
+        assert!(err.set_debug_context(DebugInfo::new("resolved")));
+        assert_eq!(err.as_internal().unwrap().detail, "resolved");
+        assert!(!err.set_resource_info(ResourceInfo::new("t", "n")));
+    }
+
+    #[test]
+    fn lookup_category_by_gts_type_reverses_gts_type() {
+        for category in Category::ALL {
+            assert_eq!(lookup_category_by_gts_type(category.gts_type()), Some(category));
+        }
+        assert_eq!(lookup_category_by_gts_type("gts.cf.core.errors.err.v1~cf.core.errors.nope.v1~"), None);
+    }
+
+    #[test]
+    fn lookup_by_status_returns_every_sharing_category() {
+        assert_eq!(
+            lookup_by_status(400),
+            vec![Category::InvalidArgument, Category::FailedPrecondition, Category::OutOfRange]
+        );
+        assert_eq!(lookup_by_status(409), vec![Category::AlreadyExists, Category::Aborted]);
+        assert_eq!(lookup_by_status(500), vec![Category::Unknown, Category::Internal, Category::DataLoss]);
+        assert_eq!(lookup_by_status(404), vec![Category::NotFound]);
+        assert!(lookup_by_status(418).is_empty());
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn context_types_are_deserializable() {
-        // FieldViolation
-        let json = r#"{"field":"email","description":"is required","reason":"REQUIRED"}"#;
-        let fv: FieldViolation = serde_json::from_str(json).unwrap();
-        assert_eq!(fv.field, "email");
-        assert_eq!(fv.description, "is required");
-        assert_eq!(fv.reason, "REQUIRED");
+    fn cancelled_with_reason_records_reason_in_message() {
+        let err = CanonicalError::cancelled_with_reason(RequestInfo::new("req-1"), CancellationReason::ServerShutdown);
+        assert_eq!(err.category(), Category::Cancelled);
+        assert_eq!(err.message(), "Operation cancelled: server_shutdown");
+        assert_eq!(err.cancellation_reason(), Some(&CancellationReason::ServerShutdown));
+        let parts = err.clone().into_parts();
+        assert_eq!(CanonicalError::from_parts(parts).unwrap(), err);
 
-        // ResourceInfo
-        let json = r#"{"resource_type":"gts.cf.core.users.user.v1","resource_name":"user-123","description":"Resource not found"}"#;
-        let ri: ResourceInfo = serde_json::from_str(json).unwrap();
-        assert_eq!(ri.resource_name, "user-123");
+        let err = CanonicalError::cancelled_with_reason(RequestInfo::new("req-2"), "lease revoked");
+        assert_eq!(err.message(), "Operation cancelled: lease revoked");
+        assert_eq!(err.cancellation_reason(), Some(&CancellationReason::Other("lease revoked".into())));
+
+        let err = CanonicalError::cancelled(RequestInfo::new("req-3"));
+        assert_eq!(err.message(), "Operation cancelled by the client");
+        assert_eq!(err.cancellation_reason(), None);
     }
 
     #[test]
-    fn not_found_gts_type() {
-        let err =
-            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
-        assert_eq!(
-            err.gts_type(),
-            "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~"
-        );
+    fn invalid_argument_fields_is_none_when_empty() {
+        assert!(CanonicalError::invalid_argument_fields(Vec::new()).is_none());
+
+        let err = CanonicalError::invalid_argument_fields(vec![FieldViolation::new("name", "required", "REQUIRED")])
+            .unwrap();
+        assert_eq!(err.category(), Category::InvalidArgument);
+        assert_eq!(err.violation_count(), Some(1));
     }
 
     #[test]
-    fn not_found_status_code() {
-        let err =
-            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
-        assert_eq!(err.status_code(), 404);
+    fn context_json_matches_problem_context() {
+        let errors = [
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "u-1"))
+                .with_resource_type("gts.cf.core.users.user.v1"),
+            CanonicalError::invalid_field("email", "must contain @", "INVALID_FORMAT")
+                .with_resource_type("gts.cf.core.users.user.v1"),
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(5)),
+            CanonicalError::internal(DebugInfo::new("boom")),
+        ];
+        for err in errors {
+            assert_eq!(err.context_json(), Problem::from(err.clone()).context, "{err}");
+        }
     }
 
+    #[cfg(feature = "hash")]
     #[test]
-    fn not_found_title() {
-        let err =
-            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", "user-123"));
-        assert_eq!(err.title(), "Not Found");
+    fn hash_agrees_with_eq_for_dedup() {
+        use std::collections::HashSet;
+
+        let not_found = |name: &str| {
+            CanonicalError::not_found(ResourceInfo::new("gts.cf.core.users.user.v1", name))
+                .with_resource_type("gts.cf.core.users.user.v1")
+        };
+        let localized = || not_found("u-1").localize("de", "Nicht gefunden").localize("fr", "Introuvable");
+        let mut seen = HashSet::new();
+        assert!(seen.insert(localized()));
+        assert!(!seen.insert(localized()));
+        // Decoded contexts carry a placeholder `gts_type`, which is not hashed.
+        assert!(seen.insert(not_found("u-1")));
+        assert!(!seen.insert(CanonicalError::try_from(Problem::from(not_found("u-1"))).unwrap()));
+        assert!(seen.insert(not_found("u-2")));
+        assert_eq!(seen.len(), 3);
+
+        let info: HashSet<ErrorInfo> =
+            [ErrorInfo::new("R", "D").with_metadata("k", "v"), ErrorInfo::new("R", "D").with_metadata("k", "v")].into();
+        assert_eq!(info.len(), 1);
     }
 
     #[test]
@@ -1320,6 +6318,163 @@ mod tests {
         assert!(json.get("trace_id").is_none());
     }
 
+    #[test]
+    fn problem_with_instance_and_trace_id_serialize_when_set() {
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")))
+            .with_instance("/api/users/n")
+            .with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["instance"], "/api/users/n");
+        assert_eq!(json["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    }
+
+    #[test]
+    fn problem_json_excludes_instance_when_none() {
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")))
+            .with_trace_id("abc");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert!(json.get("instance").is_none());
+        assert_eq!(json["trace_id"], "abc");
+    }
+
+    #[test]
+    fn problem_extensions_are_flattened_to_top_level() {
+        let problem = Problem::from(CanonicalError::resource_exhausted(QuotaFailure::new(vec![])))
+            .with_extension("balance", 30)
+            .with_extension("accounts", serde_json::json!(["/account/12345", "/account/67890"]));
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["balance"], 30);
+        assert_eq!(json["accounts"][1], "/account/67890");
+        assert!(json.get("extensions").is_none());
+    }
+
+    #[test]
+    fn problem_extensions_ignore_reserved_keys() {
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")))
+            .with_extension("status", 200)
+            .with_extension("type", "about:blank");
+        assert!(problem.extensions.is_empty());
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["type"], "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~");
+    }
+
+    #[test]
+    fn problem_deserialize_preserves_unknown_top_level_keys() {
+        let json = r#"{
+            "type": "gts.cf.core.errors.err.v1~cf.core.errors.internal.v1~",
+            "title": "Internal",
+            "status": 500,
+            "detail": "An internal error occurred",
+            "context": {"detail": "test", "stack_entries": []},
+            "balance": 30,
+            "accounts": ["/account/12345"]
+        }"#;
+        let problem: Problem = serde_json::from_str(json).unwrap();
+        assert_eq!(problem.extensions.len(), 2);
+        assert_eq!(problem.extensions["balance"], 30);
+        let reserialized = serde_json::to_value(&problem).unwrap();
+        assert_eq!(reserialized["accounts"][0], "/account/12345");
+    }
+
+    #[test]
+    fn error_info_with_max_metadata_truncates_deterministically() {
+        let info = ErrorInfo::new("R", "D")
+            .with_metadata("d", "4")
+            .with_metadata("b", "2")
+            .with_metadata("a", "1")
+            .with_metadata("c", "3")
+            .with_max_metadata(2);
+        assert_eq!(info.metadata.len(), 3);
+        assert_eq!(info.metadata["a"], "1");
+        assert_eq!(info.metadata["b"], "2");
+        assert_eq!(info.metadata[METADATA_TRUNCATED_KEY], "true");
+    }
+
+    #[test]
+    fn error_info_with_max_metadata_within_cap_is_untouched() {
+        let info = ErrorInfo::new("R", "D")
+            .with_metadata("a", "1")
+            .with_max_metadata(2);
+        assert_eq!(info.metadata.len(), 1);
+        assert!(!info.metadata.contains_key(METADATA_TRUNCATED_KEY));
+    }
+
+    #[test]
+    fn retry_after_seconds_only_for_service_unavailable() {
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(30));
+        assert_eq!(err.retry_after_seconds(), Some(30));
+        assert_eq!(Problem::from(err).retry_after_header().as_deref(), Some("30"));
+
+        let err = CanonicalError::not_found(ResourceInfo::new("t", "n"));
+        assert_eq!(err.retry_after_seconds(), None);
+        assert_eq!(Problem::from(err).retry_after_header(), None);
+    }
+
+    #[test]
+    fn with_type_url_keeps_gts_urn_as_extension() {
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")))
+            .with_type_url("https://errors.example.com/");
+        assert_eq!(problem.problem_type, "https://errors.example.com/not_found");
+        assert_eq!(
+            problem.extensions["gts_type"],
+            "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~"
+        );
+        let err = CanonicalError::try_from(problem.clone()).unwrap();
+        assert_eq!(err.category(), Category::NotFound);
+
+        let again = problem.with_type_url("https://other.example.com");
+        assert_eq!(again.problem_type, "https://errors.example.com/not_found");
+    }
+
+    #[test]
+    fn response_headers_in_documented_order() {
+        let problem = Problem::from(
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(30)).with_trace_id("trace-1"),
+        );
+        assert_eq!(
+            problem.response_headers(),
+            vec![
+                ("Content-Type", "application/problem+json".to_string()),
+                ("Retry-After", "30".to_string()),
+                ("X-Trace-Id", "trace-1".to_string()),
+            ]
+        );
+
+        let problem = Problem::from(CanonicalError::not_found(ResourceInfo::new("t", "n")));
+        assert_eq!(problem.response_headers(), vec![("Content-Type", "application/problem+json".to_string())]);
+    }
+
+    #[test]
+    fn retry_after_seconds_none_for_absolute_retry_at() {
+        let err = CanonicalError::service_unavailable(RetryInfo::at("2026-02-25T10:00:00Z"));
+        assert_eq!(err.retry_after_seconds(), None);
+        assert_eq!(Problem::from(err).retry_after_header(), None);
+    }
+
+    #[test]
+    fn retry_info_serializes_only_populated_field() {
+        let relative = serde_json::to_value(RetryInfo::after_seconds(30)).unwrap();
+        assert_eq!(relative, serde_json::json!({ "retry_after_seconds": 30 }));
+
+        let absolute = serde_json::to_value(RetryInfo::at("2026-02-25T10:00:00Z")).unwrap();
+        assert_eq!(absolute, serde_json::json!({ "retry_at": "2026-02-25T10:00:00Z" }));
+    }
+
+    #[test]
+    fn retry_info_at_roundtrips_through_problem() {
+        let original = CanonicalError::service_unavailable(RetryInfo::at("2026-02-25T10:00:00Z"));
+        let problem = Problem::from_error(original);
+        let reconstructed = CanonicalError::try_from(problem).unwrap();
+        match reconstructed {
+            CanonicalError::ServiceUnavailable { ctx, .. } => {
+                assert_eq!(ctx.retry_at.as_deref(), Some("2026-02-25T10:00:00Z"));
+                assert_eq!(ctx.retry_after_seconds, None);
+            }
+            other => panic!("expected ServiceUnavailable, got: {other:?}"),
+        }
+    }
+
     #[test]
     fn validation_field_violations_serialization() {
         let v = Validation::fields(vec![FieldViolation::new(
@@ -1346,9 +6501,9 @@ mod tests {
         assert_eq!(json["constraint"], "too many items");
     }
 
-    #[test]
-    fn all_16_categories_convert_to_problem() {
-        let errors: Vec<CanonicalError> = vec![
+    /// Helper: one error per category, in `Category::ALL` order.
+    fn sample_error_per_category() -> Vec<CanonicalError> {
+        vec![
             CanonicalError::cancelled(RequestInfo::new("req-1")),
             CanonicalError::unknown("unknown error"),
             CanonicalError::invalid_argument(Validation::format("bad")),
@@ -1365,7 +6520,12 @@ mod tests {
             CanonicalError::service_unavailable(RetryInfo::after_seconds(10)),
             CanonicalError::data_loss(ResourceInfo::new("t", "n")),
             CanonicalError::unauthenticated(ErrorInfo::new("R", "D")),
-        ];
+        ]
+    }
+
+    #[test]
+    fn all_16_categories_convert_to_problem() {
+        let errors = sample_error_per_category();
         assert_eq!(errors.len(), 16);
         for err in errors {
             let problem = Problem::from(err);
@@ -1375,6 +6535,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn grpc_code_mapping_matches_google_canonical_codes() {
+        let cases: Vec<(CanonicalError, i32)> = sample_error_per_category().into_iter().zip(1..).collect();
+        assert_eq!(cases.len(), 16);
+        for (err, expected) in cases {
+            assert_eq!(err.grpc_code(), expected, "grpc_code mismatch for {err}");
+        }
+    }
+
+    #[test]
+    fn grpc_trailers_percent_encode_message() {
+        let err = CanonicalError::not_found(ResourceInfo::new("user", "u-1"))
+            .with_message("100% gone: caf\u{e9}\n");
+        assert_eq!(
+            err.grpc_trailers(),
+            vec![
+                ("grpc-status".to_string(), "5".to_string()),
+                ("grpc-message".to_string(), "100%25 gone: caf%C3%A9%0A".to_string()),
+            ]
+        );
+    }
+
     // --- New tests for resource_error! macro ---
 
     #[test]
@@ -1470,6 +6652,47 @@ mod tests {
         assert_eq!(info.stack_entries, vec!["cf_users::repo::find_by_id (src/repo.rs:42)"]);
     }
 
+    #[test]
+    fn with_debug_info_if_true_attaches() {
+        let err = CanonicalError::internal(DebugInfo::new("bug"))
+            .with_debug_info_if(true, || DebugInfo::new("expensive detail"));
+        assert_eq!(err.debug_info().unwrap().detail, "expensive detail");
+    }
+
+    #[test]
+    fn with_debug_info_if_false_does_not_call_closure() {
+        let mut called = false;
+        let err = CanonicalError::internal(DebugInfo::new("bug")).with_debug_info_if(false, || {
+            called = true;
+            DebugInfo::new("expensive detail")
+        });
+        assert!(!called, "closure must not run when cond is false");
+        assert!(err.debug_info().is_none());
+    }
+
+    #[test]
+    fn debug_info_from_error_chain_records_each_cause() {
+        #[derive(Debug)]
+        struct LoadUserError(io::Error);
+
+        impl fmt::Display for LoadUserError {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "failed to load user")
+            }
+        }
+
+        impl std::error::Error for LoadUserError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let err = LoadUserError(io::Error::other("connection reset"));
+        let info = DebugInfo::from_error_chain(&err);
+        assert_eq!(info.detail, "failed to load user");
+        assert_eq!(info.stack_entries, vec!["caused by: connection reset"]);
+    }
+
     #[test]
     fn default_construction_has_no_debug_info() {
         let err =
@@ -1545,6 +6768,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+                "code": "not_found",
                 "title": "Not Found",
                 "status": 404,
                 "detail": "Resource not found",
@@ -1570,6 +6794,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.already_exists.v1~",
+                "code": "already_exists",
                 "title": "Already Exists",
                 "status": 409,
                 "detail": "Resource already exists",
@@ -1595,6 +6820,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.data_loss.v1~",
+                "code": "data_loss",
                 "title": "Data Loss",
                 "status": 500,
                 "detail": "Data loss detected",
@@ -1645,6 +6871,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.invalid_argument.v1~",
+                "code": "invalid_argument",
                 "title": "Invalid Argument",
                 "status": 400,
                 "detail": "Request validation failed",
@@ -1682,6 +6909,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.out_of_range.v1~",
+                "code": "out_of_range",
                 "title": "Out of Range",
                 "status": 400,
                 "detail": "Page 50 is beyond the last page (12)",
@@ -1709,6 +6937,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.permission_denied.v1~",
+                "code": "permission_denied",
                 "title": "Permission Denied",
                 "status": 403,
                 "detail": "You do not have permission to perform this operation",
@@ -1739,6 +6968,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.aborted.v1~",
+                "code": "aborted",
                 "title": "Aborted",
                 "status": 409,
                 "detail": "Operation aborted due to concurrency conflict",
@@ -1768,6 +6998,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.unimplemented.v1~",
+                "code": "unimplemented",
                 "title": "Unimplemented",
                 "status": 501,
                 "detail": "This operation is not implemented",
@@ -1800,6 +7031,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.failed_precondition.v1~",
+                "code": "failed_precondition",
                 "title": "Failed Precondition",
                 "status": 400,
                 "detail": "Operation precondition not met",
@@ -1832,6 +7064,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.internal.v1~",
+                "code": "internal",
                 "title": "Internal",
                 "status": 500,
                 "detail": "An internal error occurred. Please retry later.",
@@ -1857,6 +7090,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.deadline_exceeded.v1~",
+                "code": "deadline_exceeded",
                 "title": "Deadline Exceeded",
                 "status": 504,
                 "detail": "Operation did not complete within the allowed time",
@@ -1881,6 +7115,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.cancelled.v1~",
+                "code": "cancelled",
                 "title": "Cancelled",
                 "status": 499,
                 "detail": "Operation cancelled by the client",
@@ -1909,6 +7144,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.unauthenticated.v1~",
+                "code": "unauthenticated",
                 "title": "Unauthenticated",
                 "status": 401,
                 "detail": "Authentication required",
@@ -1936,6 +7172,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.resource_exhausted.v1~",
+                "code": "resource_exhausted",
                 "title": "Resource Exhausted",
                 "status": 429,
                 "detail": "Quota exceeded",
@@ -1962,6 +7199,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.service_unavailable.v1~",
+                "code": "service_unavailable",
                 "title": "Unavailable",
                 "status": 503,
                 "detail": "Service temporarily unavailable",
@@ -1982,6 +7220,7 @@ mod tests {
             json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.unknown.v1~",
+                "code": "unknown",
                 "title": "Unknown",
                 "status": 500,
                 "detail": "Unexpected response from payment provider",
@@ -2017,6 +7256,7 @@ mod tests {
             debug_json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+                "code": "not_found",
                 "title": "Not Found",
                 "status": 404,
                 "detail": "Resource not found",
@@ -2042,6 +7282,7 @@ mod tests {
             prod_json,
             serde_json::json!({
                 "type": "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~",
+                "code": "not_found",
                 "title": "Not Found",
                 "status": 404,
                 "detail": "Resource not found",
@@ -2069,7 +7310,6 @@ mod tests {
                 "$schema": "http://json-schema.org/draft-07/schema#",
                 "additionalProperties": false,
                 "type": "object",
-                "required": ["retry_after_seconds"],
                 "properties": {
                     "gts_type": {
                         "description": "GTS schema identifier",
@@ -2081,7 +7321,10 @@ mod tests {
                     "retry_after_seconds": {
                         "format": "uint64",
                         "minimum": 0,
-                        "type": "integer"
+                        "type": ["integer", "null"]
+                    },
+                    "retry_at": {
+                        "type": ["string", "null"]
                     }
                 }
             })
@@ -2110,6 +7353,11 @@ mod tests {
                     },
                     "request_id": {
                         "type": "string"
+                    },
+                    "elapsed_ms": {
+                        "format": "uint64",
+                        "minimum": 0,
+                        "type": ["integer", "null"]
                     }
                 }
             })
@@ -2132,6 +7380,9 @@ mod tests {
                     "description": {
                         "type": "string"
                     },
+                    "parent": {
+                        "anyOf": [{ "$ref": "#" }, { "type": "null" }]
+                    },
                     "gts_type": {
                         "description": "GTS schema identifier",
                         "format": "gts-schema-id",
@@ -2343,6 +7594,12 @@ mod tests {
                     "subject": {
                         "type": "string"
                     },
+                    "subject_path": {
+                        "items": {
+                            "type": "string"
+                        },
+                        "type": ["array", "null"]
+                    },
                     "type": {
                         "type": "string"
                     }
@@ -2464,7 +7721,7 @@ mod tests {
                     variant("out_of_range",        "gts://gts.cf.core.errors.validation.v1~"),
                     variant("unimplemented",       "gts://gts.cf.core.errors.error_info.v1~"),
                     variant("internal",            "gts://gts.cf.core.errors.debug_info.v1~"),
-                    variant("unavailable",         "gts://gts.cf.core.errors.retry_info.v1~"),
+                    variant("service_unavailable", "gts://gts.cf.core.errors.retry_info.v1~"),
                     variant("data_loss",           "gts://gts.cf.core.errors.resource_info.v1~"),
                     variant("unauthenticated",     "gts://gts.cf.core.errors.error_info.v1~")
                 ]
@@ -2478,8 +7735,6 @@ mod tests {
 
     #[test]
     fn validate_all_gts_ids() {
-        use gts::schema::GtsSchema;
-
         // Validate all 16 category GTS type IDs
         let errors = vec![
             CanonicalError::cancelled(RequestInfo::new("r")),
@@ -2527,6 +7782,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn openapi_components_cover_all_schemas_with_component_refs() {
+        let components = openapi_components();
+        let schemas = components.as_object().unwrap();
+        for name in [
+            "RetryInfoV1",
+            "RequestInfoV1",
+            "ResourceInfoV1",
+            "ErrorInfoV1",
+            "FieldViolationV1",
+            "DebugInfoV1",
+            "QuotaViolationV1",
+            "QuotaFailureV1",
+            "PreconditionViolationV1",
+            "PreconditionFailureV1",
+            "Validation",
+            "CanonicalError",
+            "Problem",
+        ] {
+            let schema = schemas.get(name).unwrap_or_else(|| panic!("missing component {name}"));
+            assert!(schema.get("$id").is_none(), "{name} keeps $id");
+        }
+        assert_eq!(schemas.len(), 13);
+
+        assert_eq!(
+            schemas["QuotaFailureV1"]["properties"]["violations"]["items"]["$ref"],
+            "#/components/schemas/QuotaViolationV1"
+        );
+        assert_eq!(
+            schemas["Validation"]["oneOf"][0]["properties"]["field_violations"]["items"]["$ref"],
+            "#/components/schemas/FieldViolationV1"
+        );
+        assert_eq!(
+            schemas["CanonicalError"]["oneOf"][4]["properties"]["context"]["$ref"],
+            "#/components/schemas/ResourceInfoV1"
+        );
+
+        let text = components.to_string();
+        assert!(!text.contains("gts://"), "unrewritten gts ref in {text}");
+        assert!(!text.contains("#/$defs/"), "unrewritten $defs ref in {text}");
+    }
+
+    #[test]
+    fn all_schemas_pair_each_id_with_its_schema() {
+        let schemas = all_schemas();
+        let ids: Vec<&str> = schemas.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, all_schema_ids());
+        for (id, schema) in &schemas {
+            assert_eq!(schema["$id"], format!("gts://{id}"));
+        }
+        assert_eq!(
+            all_schema_ids(),
+            [
+                RetryInfoV1::SCHEMA_ID,
+                RequestInfoV1::SCHEMA_ID,
+                ResourceInfoV1::SCHEMA_ID,
+                ErrorInfoV1::SCHEMA_ID,
+                FieldViolationV1::SCHEMA_ID,
+                DebugInfoV1::SCHEMA_ID,
+                QuotaViolationV1::SCHEMA_ID,
+                QuotaFailureV1::SCHEMA_ID,
+                PreconditionViolationV1::SCHEMA_ID,
+                PreconditionFailureV1::SCHEMA_ID,
+                Validation::SCHEMA_ID,
+                "gts.cf.core.errors.canonical_error.v1~",
+            ]
+        );
+    }
+
     // =========================================================================
     // Round-trip tests: CanonicalError → Problem → CanonicalError
     // =========================================================================
@@ -2636,6 +7960,36 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn service_unavailable_spelling_agrees_everywhere() {
+        use gts::schema::GtsSchema;
+
+        let err = CanonicalError::service_unavailable(RetryInfo::after_seconds(30));
+        let name = Category::ServiceUnavailable.name();
+        assert_eq!(name, "service_unavailable");
+        assert_eq!(err.to_string(), "service_unavailable: Service temporarily unavailable");
+        assert_eq!(parse_category(err.gts_type()).unwrap(), name);
+        assert_eq!(serde_json::to_value(&err).unwrap()["category"], name);
+
+        let mut problem = Problem::from_error(err.clone());
+        assert_eq!(problem.code.as_deref(), Some(name));
+        problem.code = None;
+        assert_eq!(CanonicalError::try_from(problem).unwrap(), err);
+
+        let schema = CanonicalError::gts_schema_with_refs();
+        let consts: Vec<&str> = schema["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|variant| variant["properties"]["category"]["const"].as_str().unwrap())
+            .collect();
+        let names: Vec<&str> = Category::ALL.iter().map(|c| c.name()).collect();
+        assert_eq!(consts, names);
+        for category in Category::ALL {
+            assert_eq!(parse_category(category.gts_type()).unwrap(), category.name());
+        }
+    }
+
     #[test]
     fn roundtrip_data_loss() {
         assert_roundtrip(&CanonicalError::data_loss(
@@ -2760,6 +8114,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn roundtrip_error_info_categories_preserve_resource_type() {
+        #[resource_error("gts.cf.core.tenants.tenant.v1")]
+        struct TenantResourceError;
+
+        let errors = [
+            TenantResourceError::permission_denied(ErrorInfo::new(
+                "CROSS_TENANT_ACCESS",
+                "auth.cyberfabric.io",
+            )),
+            TenantResourceError::aborted(ErrorInfo::new("OPTIMISTIC_LOCK_FAILURE", "cf.oagw")),
+            TenantResourceError::unimplemented(ErrorInfo::new("GRPC_ROUTING", "cf.oagw")),
+            TenantResourceError::unauthenticated(ErrorInfo::new("TOKEN_EXPIRED", "auth.cyberfabric.io")),
+        ];
+        for original in errors {
+            let problem = Problem::from_error(original.clone());
+            assert_eq!(problem.context["resource_type"], "gts.cf.core.tenants.tenant.v1");
+            let reconstructed = CanonicalError::try_from(problem)
+                .unwrap_or_else(|e| panic!("round-trip failed for {original}: {e}"));
+            assert_eq!(reconstructed.resource_type(), Some("gts.cf.core.tenants.tenant.v1"));
+            // The injected key must not leak into the typed context
+            assert_eq!(original.context_value(), reconstructed.context_value());
+        }
+    }
+
+    #[test]
+    fn strip_injected_resource_type_keeps_resource_info_field() {
+        let mut ctx = serde_json::json!({"resource_type": "t", "resource_name": "n"});
+        strip_injected_resource_type("not_found", &mut ctx);
+        assert_eq!(ctx["resource_type"], "t");
+
+        let mut ctx = serde_json::json!({"resource_type": "t", "reason": "R", "domain": "D"});
+        strip_injected_resource_type("permission_denied", &mut ctx);
+        assert!(ctx.get("resource_type").is_none());
+    }
+
     #[test]
     fn roundtrip_without_resource_type() {
         // Internal uses DebugInfo — no resource_type field in the context struct
@@ -2809,14 +8199,17 @@ mod tests {
     #[test]
     fn try_from_malformed_type_uri() {
         let problem = Problem {
-            problem_type: "not-a-gts-uri".to_string(),
-            title: "Unknown".to_string(),
+            problem_type: "not-a-gts-uri".into(),
+            title: "Unknown".into(),
             status: 500,
             detail: "test".to_string(),
             instance: None,
             trace_id: None,
+            code: None,
+            error_code: None,
             context: serde_json::json!({}),
             debug: None,
+            extensions: HashMap::new(),
         };
         let err = CanonicalError::try_from(problem).unwrap_err();
         assert!(matches!(err, ProblemConversionError::InvalidType(_)));
@@ -2825,14 +8218,17 @@ mod tests {
     #[test]
     fn try_from_unknown_category() {
         let problem = Problem {
-            problem_type: "gts.cf.core.errors.err.v1~cf.core.errors.nonexistent.v1~".to_string(),
-            title: "Unknown".to_string(),
+            problem_type: "gts.cf.core.errors.err.v1~cf.core.errors.nonexistent.v1~".into(),
+            title: "Unknown".into(),
             status: 500,
             detail: "test".to_string(),
             instance: None,
             trace_id: None,
+            code: None,
+            error_code: None,
             context: serde_json::json!({}),
             debug: None,
+            extensions: HashMap::new(),
         };
         let err = CanonicalError::try_from(problem).unwrap_err();
         match err {
@@ -2844,14 +8240,17 @@ mod tests {
     #[test]
     fn try_from_wrong_context_shape() {
         let problem = Problem {
-            problem_type: "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~".to_string(),
-            title: "Not Found".to_string(),
+            problem_type: "gts.cf.core.errors.err.v1~cf.core.errors.not_found.v1~".into(),
+            title: "Not Found".into(),
             status: 404,
             detail: "Resource not found".to_string(),
             instance: None,
             trace_id: None,
+            code: None,
+            error_code: None,
             context: serde_json::json!({"unexpected": "shape"}),
             debug: None,
+            extensions: HashMap::new(),
         };
         let err = CanonicalError::try_from(problem).unwrap_err();
         assert!(matches!(err, ProblemConversionError::ContextDeserializationFailed { .. }));
@@ -2929,4 +8328,181 @@ mod tests {
         assert!(problem.trace_id.is_none());
         assert!(problem.debug.is_none());
     }
+
+    // =========================================================================
+    // CanonicalError serde (tagged form)
+    // =========================================================================
+
+    #[test]
+    fn serde_roundtrip_all_16_categories() {
+        #[resource_error("gts.cf.core.users.user.v1")]
+        struct UserResourceError;
+
+        let errors = vec![
+            CanonicalError::cancelled(RequestInfo::new("req-1")),
+            CanonicalError::unknown("unknown error"),
+            CanonicalError::invalid_argument(Validation::fields([FieldViolation::new(
+                "email",
+                "is required",
+                "REQUIRED",
+            )])),
+            CanonicalError::deadline_exceeded(RequestInfo::new("req-2")),
+            UserResourceError::not_found("user-123"),
+            UserResourceError::already_exists("bob@example.com"),
+            CanonicalError::permission_denied(
+                ErrorInfo::new("CROSS_TENANT_ACCESS", "auth.cyberfabric.io")
+                    .with_metadata("tenant_id", "t-1"),
+            ),
+            CanonicalError::resource_exhausted(QuotaFailure::new([QuotaViolation::new(
+                "requests",
+                "Rate limit exceeded",
+            )])),
+            CanonicalError::failed_precondition(PreconditionFailure::new([
+                PreconditionViolation::new("STATE", "tenant.users", "Remove all active users"),
+            ])),
+            CanonicalError::aborted(ErrorInfo::new("OPTIMISTIC_LOCK_FAILURE", "cf.oagw")),
+            CanonicalError::out_of_range(Validation::constraint("Page 50 is beyond the last page")),
+            CanonicalError::unimplemented(ErrorInfo::new("GRPC_STREAMING", "cf.core")),
+            CanonicalError::internal(DebugInfo::new("invariant violated")),
+            CanonicalError::service_unavailable(RetryInfo::after_seconds(30)),
+            UserResourceError::data_loss("backup-42"),
+            CanonicalError::unauthenticated(ErrorInfo::new("TOKEN_EXPIRED", "auth.cyberfabric.io")),
+        ];
+        assert_eq!(errors.len(), 16);
+        for original in errors {
+            let json = serde_json::to_value(&original).unwrap();
+            let reconstructed: CanonicalError = serde_json::from_value(json.clone())
+                .unwrap_or_else(|e| panic!("deserialization failed for {json}: {e}"));
+            assert_eq!(original.gts_type(), reconstructed.gts_type(), "gts_type mismatch");
+            assert_eq!(original.message(), reconstructed.message(), "message mismatch");
+            assert_eq!(original.resource_type(), reconstructed.resource_type(), "resource_type mismatch");
+            assert_eq!(json, serde_json::to_value(&reconstructed).unwrap(), "context mismatch");
+        }
+    }
+
+    #[test]
+    fn serde_tagged_shape_matches_schema() {
+        #[resource_error("gts.cf.core.users.user.v1")]
+        struct UserResourceError;
+
+        let err = UserResourceError::not_found("user-123")
+            .with_debug_info(DebugInfo::new("never serialized"));
+        let json = serde_json::to_value(&err).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "category": "not_found",
+                "message": "Resource not found",
+                "resource_type": "gts.cf.core.users.user.v1",
+                "context": {
+                    "resource_type": "gts.cf.core.users.user.v1",
+                    "resource_name": "user-123",
+                    "description": "Resource not found"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn serde_embedded_in_derived_struct() {
+        #[derive(Serialize, Deserialize)]
+        struct JobResult {
+            job_id: String,
+            error: Option<CanonicalError>,
+        }
+
+        let result = JobResult {
+            job_id: "job-1".to_string(),
+            error: Some(CanonicalError::service_unavailable(RetryInfo::after_seconds(5))),
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: JobResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.job_id, "job-1");
+        let err = parsed.error.expect("error should be present");
+        assert_eq!(err.status_code(), 503);
+        assert_eq!(err.message(), "Service temporarily unavailable");
+    }
+
+    #[test]
+    fn serde_unknown_category_is_rejected() {
+        let json = serde_json::json!({
+            "category": "nonexistent",
+            "message": "test",
+            "context": {}
+        });
+        let err = serde_json::from_value::<CanonicalError>(json).unwrap_err();
+        assert!(err.to_string().contains("unknown canonical error category: nonexistent"));
+    }
+
+    // =========================================================================
+    // google.rpc.* compatibility
+    // =========================================================================
+
+    #[test]
+    fn google_bad_request_camel_case_field_violations() {
+        let json = serde_json::json!({
+            "@type": "type.googleapis.com/google.rpc.BadRequest",
+            "fieldViolations": [
+                { "field": "email", "description": "must be valid", "reason": "INVALID_FORMAT" },
+                { "field": "age", "description": "must be at least 18" }
+            ]
+        });
+        let validation = Validation::from_google_bad_request(&json).unwrap();
+        match validation {
+            Validation::FieldViolations { field_violations } => {
+                assert_eq!(field_violations.len(), 2);
+                assert_eq!(field_violations[0].field, "email");
+                assert_eq!(field_violations[0].reason, "INVALID_FORMAT");
+                assert_eq!(field_violations[1].field, "age");
+                assert_eq!(field_violations[1].reason, "");
+            }
+            other => panic!("expected FieldViolations, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn google_quota_and_precondition_failures() {
+        let quota = google_compat::quota_failure(&serde_json::json!({
+            "violations": [{ "subject": "project:123", "description": "Daily limit exceeded" }]
+        }))
+        .unwrap();
+        assert_eq!(quota.violations[0].subject, "project:123");
+
+        let precondition = google_compat::precondition_failure(&serde_json::json!({
+            "violations": [{ "type": "TOS", "subject": "google.com/cloud", "description": "Terms not accepted" }]
+        }))
+        .unwrap();
+        assert_eq!(precondition.violations[0].precondition_type, "TOS");
+        assert_eq!(precondition.violations[0].subject, "google.com/cloud");
+    }
+
+    #[test]
+    fn problem_from_ndjson_reader_yields_one_problem_per_line() {
+        let lines = [
+            Problem::from_error(CanonicalError::not_found(ResourceInfo::new("t", "n"))),
+            Problem::from_error(CanonicalError::unknown("boom")),
+            Problem::from_error(CanonicalError::service_unavailable(RetryInfo::after_seconds(5))),
+        ]
+        .iter()
+        .map(|p| serde_json::to_string(p).unwrap())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+        let problems: Vec<Problem> = Problem::from_ndjson_reader(lines.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(problems.len(), 3);
+        assert_eq!(problems[0].status, 404);
+        assert_eq!(problems[1].detail, "boom");
+        assert_eq!(problems[2].status, 503);
+    }
+
+    #[test]
+    fn problem_from_ndjson_reader_reports_malformed_line() {
+        let ndjson = "{\"not\": \"a problem\"}\n";
+        let results: Vec<_> = Problem::from_ndjson_reader(ndjson.as_bytes()).collect();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+    }
 }